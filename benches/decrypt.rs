@@ -0,0 +1,63 @@
+//!
+//! Compare decrypting a 4 KiB blob with the precomputed RSBOX against
+//! recomputing the inverse sbox for every block like the previous implementation did.
+//!
+//! Run it with `cargo bench --bench decrypt`.
+//!
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use const_aes::Aes128CBC;
+use const_aes::utils::decrypt::{calculate_rsbox, decrypt_block};
+use const_aes::utils::key_schedule;
+
+const KEY: [u8; 16] = [
+    0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+    0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c
+];
+
+const IV: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+    0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F
+];
+
+const LENGTH: usize = 4096;
+const ITERATIONS: u32 = 50;
+
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        f();
+    }
+
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let ctx = Aes128CBC::new(KEY, IV);
+    let cipher: [u8; LENGTH + 16] = ctx.encrypt(&[0x41; LENGTH]);
+
+    //
+    // current implementation, table lookups only
+    //
+    let current = measure(|| {
+        black_box(ctx.decrypt(black_box(&cipher)));
+    });
+
+    //
+    // previous implementation, the inverse sbox is searched for every block
+    //
+    let keys = key_schedule(&KEY);
+    let previous = measure(|| {
+        for block in cipher.chunks_exact(16) {
+            black_box(calculate_rsbox());
+            black_box(decrypt_block(black_box(block.try_into().unwrap()), &keys));
+        }
+    });
+
+    println!("decrypt {} bytes with precomputed tables: {:?}", LENGTH, current);
+    println!("decrypt {} bytes recomputing the rsbox:   {:?}", LENGTH, previous);
+    println!("speedup: {:.1}x", previous.as_secs_f64() / current.as_secs_f64());
+}
@@ -0,0 +1,167 @@
+//!
+//! Procedural macros encrypting literals and files at compile time with `const_aes`.
+//!
+//! A fresh key and iv are generated each time the calling crate is compiled, the cipher
+//! is computed by `Aes128CBC::encrypt` in a const context and the expansion decrypts it at runtime.
+//! Use them through the `macros` feature of `const_aes`.
+//!
+
+use const_aes_random::random_bytes;
+use proc_macro::{Delimiter, Literal, TokenStream, TokenTree};
+
+/// encrypt a string literal at compile time, the expansion evaluates to the decrypted `String`
+///
+/// ```ignore
+/// let secret: String = const_aes::encrypt_str!("This is a top secret message");
+/// ```
+#[proc_macro]
+pub fn encrypt_str(input: TokenStream) -> TokenStream {
+    let literal = match parse_literal(input) {
+        Ok(literal) => literal,
+        Err(err) => return err,
+    };
+
+    if !is_str_literal(&literal.to_string()) {
+        return compile_error("encrypt_str! expects a string literal");
+    }
+
+    expand(
+        &literal.to_string(),
+        "&str",
+        "::const_aes::utils::str_to_bytes(LITERAL)",
+        &decrypt("match ::const_aes::__private::String::from_utf8(plain) {
+            Ok(plain) => plain,
+            Err(_) => panic!(\"Invalid UTF-8\"),
+        }"),
+    )
+}
+
+/// encrypt a byte string literal at compile time, the expansion evaluates to the decrypted `Vec<u8>`
+///
+/// ```ignore
+/// let secret: Vec<u8> = const_aes::encrypt_bytes!(b"\xde\xad\xbe\xef");
+/// ```
+#[proc_macro]
+pub fn encrypt_bytes(input: TokenStream) -> TokenStream {
+    let literal = match parse_literal(input) {
+        Ok(literal) => literal,
+        Err(err) => return err,
+    };
+
+    if !is_byte_str_literal(&literal.to_string()) {
+        return compile_error("encrypt_bytes! expects a byte string literal");
+    }
+
+    expand(&literal.to_string(), "&[u8]", "::const_aes::utils::bytes_to_array(LITERAL)", &decrypt("plain"))
+}
+
+///
+/// encrypt a file at compile time, the expansion evaluates to a `const_aes::EncryptedFile`.
+///
+/// The path is relative to the directory of the calling crate's `Cargo.toml`. The file is
+/// included with `include_bytes!` so the crate is rebuilt when it changes, but only the cipher
+/// ends up in the binary. The expansion is a constant expression and may initialize a static.
+///
+/// ```ignore
+/// static CONFIG: const_aes::EncryptedFile = const_aes::include_encrypted!("assets/config.toml");
+///
+/// let config: Vec<u8> = CONFIG.decrypt();
+/// ```
+#[proc_macro]
+pub fn include_encrypted(input: TokenStream) -> TokenStream {
+    let literal = match parse_literal(input) {
+        Ok(literal) => literal,
+        Err(err) => return err,
+    };
+
+    if !is_str_literal(&literal.to_string()) {
+        return compile_error("include_encrypted! expects a string literal path");
+    }
+
+    expand(
+        &format!("::core::include_bytes!(::core::concat!(::core::env!(\"CARGO_MANIFEST_DIR\"), \"/\", {literal}))"),
+        "&[u8]",
+        "::const_aes::utils::bytes_to_array(LITERAL)",
+        "::const_aes::EncryptedFile::new(CTX, &CIPHER)",
+    )
+}
+
+///
+/// build the expansion shared by the macros.
+///
+/// `to_array` converts `LITERAL`, the expression `literal` of type `ty`, into a `[u8; LENGTH]`
+/// and `value` builds the value of the expression from `CTX` and `CIPHER`.
+///
+fn expand(literal: &str, ty: &str, to_array: &str, value: &str) -> TokenStream {
+    let key = random_bytes::<16>();
+    let iv = random_bytes::<16>();
+
+    format!(
+        "{{
+            const LITERAL: {ty} = {literal};
+            const LENGTH: usize = LITERAL.len();
+            const CIPHER_LENGTH: usize = ::const_aes::utils::encrypt::block_length_with_pkcs7_padding(LENGTH);
+
+            const KEY: [u8; 16] = {key:?};
+            const IV: [u8; 16] = {iv:?};
+
+            //
+            // encrypt at compile time, only the cipher ends up in the binary. Large inputs
+            // take a while to evaluate, which is expected rather than a runaway loop
+            //
+            const CTX: ::const_aes::Aes128CBC = ::const_aes::Aes128CBC::new(KEY, IV);
+            #[allow(long_running_const_eval)]
+            static CIPHER: [u8; CIPHER_LENGTH] = CTX.encrypt::<LENGTH, CIPHER_LENGTH>(&{to_array});
+
+            {value}
+        }}"
+    )
+    .parse()
+    .expect("the expansion is valid rust")
+}
+
+/// statements decrypting `CIPHER` at runtime, `finish` turns the decrypted `plain` vector into the value
+fn decrypt(finish: &str) -> String {
+    format!(
+        "//
+        // decrypt at runtime, hide the cipher from the optimizer so it is not folded
+        //
+        let plain: ::const_aes::__private::Vec<u8> = CTX.decrypt(::const_aes::__private::black_box(&CIPHER));
+
+        {finish}"
+    )
+}
+
+/// extract the single literal given to the macro
+fn parse_literal(input: TokenStream) -> Result<Literal, TokenStream> {
+    let mut tokens = input.into_iter();
+
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal,
+
+        //
+        // literals forwarded by macro_rules are wrapped in an invisible group
+        //
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::None => {
+            return parse_literal(group.stream());
+        }
+
+        _ => return Err(compile_error("expected a single literal")),
+    };
+
+    Ok(literal)
+}
+
+fn is_str_literal(literal: &str) -> bool {
+    literal.starts_with('"') || literal.starts_with("r\"") || literal.starts_with("r#")
+}
+
+fn is_byte_str_literal(literal: &str) -> bool {
+    literal.starts_with("b\"") || literal.starts_with("br\"") || literal.starts_with("br#")
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("::core::compile_error!({message:?})")
+        .parse()
+        .expect("compile_error! invocation is valid rust")
+}
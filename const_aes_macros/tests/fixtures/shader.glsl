@@ -0,0 +1,284 @@
+vec4 color_0 = vec4(0.134364, 0.847434, 0.763775, 1.0);
+vec4 color_1 = vec4(0.255069, 0.495435, 0.449491, 1.0);
+vec4 color_2 = vec4(0.651593, 0.788723, 0.093860, 1.0);
+vec4 color_3 = vec4(0.028347, 0.835765, 0.432767, 1.0);
+vec4 color_4 = vec4(0.762280, 0.002106, 0.445387, 1.0);
+vec4 color_5 = vec4(0.721540, 0.228762, 0.945271, 1.0);
+vec4 color_6 = vec4(0.901427, 0.030590, 0.025446, 1.0);
+vec4 color_7 = vec4(0.541412, 0.939149, 0.381204, 1.0);
+vec4 color_8 = vec4(0.216599, 0.422117, 0.029041, 1.0);
+vec4 color_9 = vec4(0.221692, 0.437888, 0.495812, 1.0);
+vec4 color_10 = vec4(0.233084, 0.230867, 0.218781, 1.0);
+vec4 color_11 = vec4(0.459603, 0.289782, 0.021490, 1.0);
+vec4 color_12 = vec4(0.837578, 0.556454, 0.642294, 1.0);
+vec4 color_13 = vec4(0.185906, 0.992543, 0.859947, 1.0);
+vec4 color_14 = vec4(0.120890, 0.332695, 0.721484, 1.0);
+vec4 color_15 = vec4(0.711192, 0.936441, 0.422107, 1.0);
+vec4 color_16 = vec4(0.830036, 0.670306, 0.303369, 1.0);
+vec4 color_17 = vec4(0.587581, 0.882479, 0.846197, 1.0);
+vec4 color_18 = vec4(0.505284, 0.589002, 0.034526, 1.0);
+vec4 color_19 = vec4(0.242740, 0.797404, 0.414314, 1.0);
+vec4 color_20 = vec4(0.173007, 0.548799, 0.703041, 1.0);
+vec4 color_21 = vec4(0.674486, 0.374703, 0.438962, 1.0);
+vec4 color_22 = vec4(0.508426, 0.778443, 0.520938, 1.0);
+vec4 color_23 = vec4(0.393255, 0.489694, 0.029575, 1.0);
+vec4 color_24 = vec4(0.043487, 0.703382, 0.983188, 1.0);
+vec4 color_25 = vec4(0.593184, 0.393600, 0.170349, 1.0);
+vec4 color_26 = vec4(0.502239, 0.982077, 0.770523, 1.0);
+vec4 color_27 = vec4(0.539617, 0.860290, 0.232176, 1.0);
+vec4 color_28 = vec4(0.513772, 0.952467, 0.577795, 1.0);
+vec4 color_29 = vec4(0.459132, 0.269279, 0.547996, 1.0);
+vec4 color_30 = vec4(0.957116, 0.005709, 0.783655, 1.0);
+vec4 color_31 = vec4(0.820486, 0.886180, 0.740503, 1.0);
+vec4 color_32 = vec4(0.809140, 0.518678, 0.561358, 1.0);
+vec4 color_33 = vec4(0.426091, 0.056123, 0.870010, 1.0);
+vec4 color_34 = vec4(0.569999, 0.199839, 0.504720, 1.0);
+vec4 color_35 = vec4(0.484925, 0.356790, 0.346078, 1.0);
+vec4 color_36 = vec4(0.538479, 0.623489, 0.612452, 1.0);
+vec4 color_37 = vec4(0.458147, 0.027975, 0.229605, 1.0);
+vec4 color_38 = vec4(0.177211, 0.584461, 0.861009, 1.0);
+vec4 color_39 = vec4(0.798439, 0.797098, 0.816437, 1.0);
+vec4 color_40 = vec4(0.255294, 0.841745, 0.673114, 1.0);
+vec4 color_41 = vec4(0.083234, 0.016691, 0.014560, 1.0);
+vec4 color_42 = vec4(0.755587, 0.249559, 0.109489, 1.0);
+vec4 color_43 = vec4(0.624802, 0.344423, 0.069515, 1.0);
+vec4 color_44 = vec4(0.159626, 0.527380, 0.168145, 1.0);
+vec4 color_45 = vec4(0.272914, 0.711590, 0.454702, 1.0);
+vec4 color_46 = vec4(0.322002, 0.473771, 0.023635, 1.0);
+vec4 color_47 = vec4(0.386557, 0.420919, 0.188039, 1.0);
+vec4 color_48 = vec4(0.108762, 0.899819, 0.510116, 1.0);
+vec4 color_49 = vec4(0.209091, 0.605649, 0.817040, 1.0);
+vec4 color_50 = vec4(0.020818, 0.017865, 0.146462, 1.0);
+vec4 color_51 = vec4(0.718835, 0.160228, 0.704606, 1.0);
+vec4 color_52 = vec4(0.678176, 0.544702, 0.220600, 1.0);
+vec4 color_53 = vec4(0.975595, 0.797811, 0.516600, 1.0);
+vec4 color_54 = vec4(0.223196, 0.648506, 0.394898, 1.0);
+vec4 color_55 = vec4(0.575846, 0.321246, 0.630948, 1.0);
+vec4 color_56 = vec4(0.058785, 0.298606, 0.967903, 1.0);
+vec4 color_57 = vec4(0.875534, 0.306387, 0.858514, 1.0);
+vec4 color_58 = vec4(0.310364, 0.939288, 0.743842, 1.0);
+vec4 color_59 = vec4(0.416172, 0.252358, 0.008480, 1.0);
+vec4 color_60 = vec4(0.878718, 0.037917, 0.819414, 1.0);
+vec4 color_61 = vec4(0.962201, 0.570281, 0.171517, 1.0);
+vec4 color_62 = vec4(0.867781, 0.973775, 0.704023, 1.0);
+vec4 color_63 = vec4(0.508874, 0.377969, 0.346931, 1.0);
+vec4 color_64 = vec4(0.205762, 0.674153, 0.432950, 1.0);
+vec4 color_65 = vec4(0.194119, 0.104424, 0.665958, 1.0);
+vec4 color_66 = vec4(0.296073, 0.499800, 0.325346, 1.0);
+vec4 color_67 = vec4(0.871622, 0.899678, 0.018093, 1.0);
+vec4 color_68 = vec4(0.200853, 0.327741, 0.987050, 1.0);
+vec4 color_69 = vec4(0.782700, 0.339096, 0.213030, 1.0);
+vec4 color_70 = vec4(0.674455, 0.837701, 0.932187, 1.0);
+vec4 color_71 = vec4(0.343850, 0.882393, 0.687110, 1.0);
+vec4 color_72 = vec4(0.484499, 0.985508, 0.234640, 1.0);
+vec4 color_73 = vec4(0.725465, 0.084680, 0.169694, 1.0);
+vec4 color_74 = vec4(0.910988, 0.212968, 0.759116, 1.0);
+vec4 color_75 = vec4(0.600209, 0.841132, 0.368108, 1.0);
+vec4 color_76 = vec4(0.340285, 0.291215, 0.867420, 1.0);
+vec4 color_77 = vec4(0.603983, 0.954307, 0.887265, 1.0);
+vec4 color_78 = vec4(0.135346, 0.551170, 0.104275, 1.0);
+vec4 color_79 = vec4(0.039138, 0.073193, 0.866168, 1.0);
+vec4 color_80 = vec4(0.788116, 0.828506, 0.340897, 1.0);
+vec4 color_81 = vec4(0.615186, 0.781904, 0.378040, 1.0);
+vec4 color_82 = vec4(0.570782, 0.223714, 0.081743, 1.0);
+vec4 color_83 = vec4(0.266724, 0.890768, 0.564447, 1.0);
+vec4 color_84 = vec4(0.925067, 0.457769, 0.277183, 1.0);
+vec4 color_85 = vec4(0.787015, 0.827768, 0.012382, 1.0);
+vec4 color_86 = vec4(0.670412, 0.091683, 0.115102, 1.0);
+vec4 color_87 = vec4(0.885060, 0.040024, 0.239633, 1.0);
+vec4 color_88 = vec4(0.988158, 0.421014, 0.115558, 1.0);
+vec4 color_89 = vec4(0.167383, 0.241420, 0.744006, 1.0);
+vec4 color_90 = vec4(0.102834, 0.910764, 0.378277, 1.0);
+vec4 color_91 = vec4(0.970264, 0.909223, 0.294024, 1.0);
+vec4 color_92 = vec4(0.253410, 0.477010, 0.100129, 1.0);
+vec4 color_93 = vec4(0.652050, 0.039620, 0.010506, 1.0);
+vec4 color_94 = vec4(0.982584, 0.295550, 0.596571, 1.0);
+vec4 color_95 = vec4(0.449845, 0.313281, 0.062965, 1.0);
+vec4 color_96 = vec4(0.913392, 0.969813, 0.969797, 1.0);
+vec4 color_97 = vec4(0.111362, 0.215193, 0.617807, 1.0);
+vec4 color_98 = vec4(0.979953, 0.542913, 0.688190, 1.0);
+vec4 color_99 = vec4(0.661834, 0.259086, 0.541602, 1.0);
+vec4 color_100 = vec4(0.307321, 0.246381, 0.081369, 1.0);
+vec4 color_101 = vec4(0.280787, 0.983377, 0.447902, 1.0);
+vec4 color_102 = vec4(0.652011, 0.643466, 0.940735, 1.0);
+vec4 color_103 = vec4(0.390479, 0.306784, 0.327241, 1.0);
+vec4 color_104 = vec4(0.316735, 0.847135, 0.893500, 1.0);
+vec4 color_105 = vec4(0.302809, 0.334333, 0.544225, 1.0);
+vec4 color_106 = vec4(0.578985, 0.595963, 0.245098, 1.0);
+vec4 color_107 = vec4(0.020374, 0.243759, 0.072328, 1.0);
+vec4 color_108 = vec4(0.551205, 0.070916, 0.075130, 1.0);
+vec4 color_109 = vec4(0.635382, 0.290822, 0.792185, 1.0);
+vec4 color_110 = vec4(0.493261, 0.862649, 0.154180, 1.0);
+vec4 color_111 = vec4(0.501430, 0.794983, 0.077107, 1.0);
+vec4 color_112 = vec4(0.949228, 0.173242, 0.776209, 1.0);
+vec4 color_113 = vec4(0.984896, 0.821550, 0.319784, 1.0);
+vec4 color_114 = vec4(0.106878, 0.514358, 0.919357, 1.0);
+vec4 color_115 = vec4(0.293489, 0.893759, 0.141681, 1.0);
+vec4 color_116 = vec4(0.910482, 0.031760, 0.316069, 1.0);
+vec4 color_117 = vec4(0.903088, 0.803856, 0.907154, 1.0);
+vec4 color_118 = vec4(0.840719, 0.746185, 0.689595, 1.0);
+vec4 color_119 = vec4(0.178155, 0.432638, 0.157897, 1.0);
+vec4 color_120 = vec4(0.714824, 0.667779, 0.252586, 1.0);
+vec4 color_121 = vec4(0.064414, 0.963386, 0.808253, 1.0);
+vec4 color_122 = vec4(0.549270, 0.541378, 0.851293, 1.0);
+vec4 color_123 = vec4(0.453310, 0.395710, 0.338669, 1.0);
+vec4 color_124 = vec4(0.257969, 0.024409, 0.646439, 1.0);
+vec4 color_125 = vec4(0.416684, 0.570604, 0.062322, 1.0);
+vec4 color_126 = vec4(0.354943, 0.138284, 0.125129, 1.0);
+vec4 color_127 = vec4(0.259113, 0.828934, 0.397797, 1.0);
+vec4 color_128 = vec4(0.401082, 0.612445, 0.233530, 1.0);
+vec4 color_129 = vec4(0.007477, 0.528702, 0.500900, 1.0);
+vec4 color_130 = vec4(0.648840, 0.438317, 0.686513, 1.0);
+vec4 color_131 = vec4(0.731422, 0.238375, 0.495072, 1.0);
+vec4 color_132 = vec4(0.478827, 0.225062, 0.412246, 1.0);
+vec4 color_133 = vec4(0.560407, 0.906940, 0.917707, 1.0);
+vec4 color_134 = vec4(0.275225, 0.646415, 0.048197, 1.0);
+vec4 color_135 = vec4(0.071551, 0.511692, 0.877424, 1.0);
+vec4 color_136 = vec4(0.159468, 0.766028, 0.883010, 1.0);
+vec4 color_137 = vec4(0.311802, 0.692557, 0.848991, 1.0);
+vec4 color_138 = vec4(0.371614, 0.701283, 0.736418, 1.0);
+vec4 color_139 = vec4(0.594578, 0.856277, 0.896604, 1.0);
+vec4 color_140 = vec4(0.960079, 0.571233, 0.176276, 1.0);
+vec4 color_141 = vec4(0.250595, 0.217619, 0.569517, 1.0);
+vec4 color_142 = vec4(0.757750, 0.052133, 0.681636, 1.0);
+vec4 color_143 = vec4(0.717153, 0.347982, 0.515056, 1.0);
+vec4 color_144 = vec4(0.164798, 0.729896, 0.040709, 1.0);
+vec4 color_145 = vec4(0.981221, 0.807944, 0.628449, 1.0);
+vec4 color_146 = vec4(0.267526, 0.912863, 0.959439, 1.0);
+vec4 color_147 = vec4(0.139126, 0.775757, 0.841931, 1.0);
+vec4 color_148 = vec4(0.659717, 0.700408, 0.445059, 1.0);
+vec4 color_149 = vec4(0.924308, 0.971208, 0.382353, 1.0);
+vec4 color_150 = vec4(0.802712, 0.432922, 0.164754, 1.0);
+vec4 color_151 = vec4(0.325467, 0.126330, 0.908885, 1.0);
+vec4 color_152 = vec4(0.959424, 0.119187, 0.600679, 1.0);
+vec4 color_153 = vec4(0.408224, 0.118090, 0.295476, 1.0);
+vec4 color_154 = vec4(0.248216, 0.749577, 0.004009, 1.0);
+vec4 color_155 = vec4(0.189839, 0.438773, 0.021035, 1.0);
+vec4 color_156 = vec4(0.627527, 0.605628, 0.835332, 1.0);
+vec4 color_157 = vec4(0.206606, 0.284782, 0.542339, 1.0);
+vec4 color_158 = vec4(0.273226, 0.585738, 0.250882, 1.0);
+vec4 color_159 = vec4(0.683527, 0.791091, 0.808655, 1.0);
+vec4 color_160 = vec4(0.973616, 0.545377, 0.490809, 1.0);
+vec4 color_161 = vec4(0.855698, 0.769067, 0.570545, 1.0);
+vec4 color_162 = vec4(0.383256, 0.284047, 0.108139, 1.0);
+vec4 color_163 = vec4(0.807549, 0.118072, 0.747265, 1.0);
+vec4 color_164 = vec4(0.545287, 0.964945, 0.761066, 1.0);
+vec4 color_165 = vec4(0.973520, 0.136594, 0.500371, 1.0);
+vec4 color_166 = vec4(0.572578, 0.311251, 0.503032, 1.0);
+vec4 color_167 = vec4(0.356819, 0.528394, 0.000845, 1.0);
+vec4 color_168 = vec4(0.442314, 0.449552, 0.304799, 1.0);
+vec4 color_169 = vec4(0.399403, 0.783087, 0.683413, 1.0);
+vec4 color_170 = vec4(0.492299, 0.647668, 0.377558, 1.0);
+vec4 color_171 = vec4(0.203914, 0.003876, 0.277621, 1.0);
+vec4 color_172 = vec4(0.598164, 0.881663, 0.829421, 1.0);
+vec4 color_173 = vec4(0.510960, 0.987018, 0.461581, 1.0);
+vec4 color_174 = vec4(0.834593, 0.408965, 0.744631, 1.0);
+vec4 color_175 = vec4(0.987592, 0.305337, 0.170313, 1.0);
+vec4 color_176 = vec4(0.620034, 0.530956, 0.359422, 1.0);
+vec4 color_177 = vec4(0.003519, 0.389163, 0.425869, 1.0);
+vec4 color_178 = vec4(0.405252, 0.861245, 0.584428, 1.0);
+vec4 color_179 = vec4(0.733831, 0.897909, 0.748773, 1.0);
+vec4 color_180 = vec4(0.492702, 0.745768, 0.640355, 1.0);
+vec4 color_181 = vec4(0.648745, 0.629675, 0.406999, 1.0);
+vec4 color_182 = vec4(0.629262, 0.633733, 0.937118, 1.0);
+vec4 color_183 = vec4(0.782474, 0.846268, 0.767500, 1.0);
+vec4 color_184 = vec4(0.815326, 0.605462, 0.349450, 1.0);
+vec4 color_185 = vec4(0.264583, 0.708020, 0.873942, 1.0);
+vec4 color_186 = vec4(0.544247, 0.152070, 0.832975, 1.0);
+vec4 color_187 = vec4(0.484543, 0.467103, 0.045388, 1.0);
+vec4 color_188 = vec4(0.510281, 0.744748, 0.422598, 1.0);
+vec4 color_189 = vec4(0.355177, 0.656844, 0.019741, 1.0);
+vec4 color_190 = vec4(0.507164, 0.946127, 0.690448, 1.0);
+vec4 color_191 = vec4(0.401924, 0.688908, 0.604994, 1.0);
+vec4 color_192 = vec4(0.208889, 0.207708, 0.886025, 1.0);
+vec4 color_193 = vec4(0.269069, 0.074885, 0.830678, 1.0);
+vec4 color_194 = vec4(0.523198, 0.368208, 0.511519, 1.0);
+vec4 color_195 = vec4(0.736726, 0.168554, 0.653067, 1.0);
+vec4 color_196 = vec4(0.713437, 0.815003, 0.269761, 1.0);
+vec4 color_197 = vec4(0.609666, 0.232114, 0.561045, 1.0);
+vec4 color_198 = vec4(0.172363, 0.789768, 0.866718, 1.0);
+vec4 color_199 = vec4(0.329644, 0.222319, 0.963788, 1.0);
+vec4 color_200 = vec4(0.706690, 0.843793, 0.030534, 1.0);
+vec4 color_201 = vec4(0.899393, 0.622452, 0.316529, 1.0);
+vec4 color_202 = vec4(0.431766, 0.761593, 0.785412, 1.0);
+vec4 color_203 = vec4(0.189901, 0.625887, 0.165630, 1.0);
+vec4 color_204 = vec4(0.973050, 0.443577, 0.913145, 1.0);
+vec4 color_205 = vec4(0.728248, 0.606260, 0.261984, 1.0);
+vec4 color_206 = vec4(0.526592, 0.138620, 0.138098, 1.0);
+vec4 color_207 = vec4(0.715750, 0.361090, 0.751376, 1.0);
+vec4 color_208 = vec4(0.240494, 0.718158, 0.718477, 1.0);
+vec4 color_209 = vec4(0.305496, 0.106385, 0.397008, 1.0);
+vec4 color_210 = vec4(0.492362, 0.099974, 0.186761, 1.0);
+vec4 color_211 = vec4(0.055343, 0.597514, 0.888876, 1.0);
+vec4 color_212 = vec4(0.216558, 0.034713, 0.703924, 1.0);
+vec4 color_213 = vec4(0.814911, 0.964122, 0.613179, 1.0);
+vec4 color_214 = vec4(0.342443, 0.837869, 0.118067, 1.0);
+vec4 color_215 = vec4(0.692637, 0.095231, 0.399706, 1.0);
+vec4 color_216 = vec4(0.495023, 0.377894, 0.168598, 1.0);
+vec4 color_217 = vec4(0.231717, 0.820150, 0.462576, 1.0);
+vec4 color_218 = vec4(0.579933, 0.211907, 0.714935, 1.0);
+vec4 color_219 = vec4(0.330117, 0.593619, 0.909487, 1.0);
+vec4 color_220 = vec4(0.994393, 0.046218, 0.797443, 1.0);
+vec4 color_221 = vec4(0.857588, 0.319574, 0.383148, 1.0);
+vec4 color_222 = vec4(0.580254, 0.918840, 0.399929, 1.0);
+vec4 color_223 = vec4(0.880030, 0.758561, 0.152273, 1.0);
+vec4 color_224 = vec4(0.913680, 0.015181, 0.145178, 1.0);
+vec4 color_225 = vec4(0.664811, 0.057120, 0.379490, 1.0);
+vec4 color_226 = vec4(0.129979, 0.462889, 0.839980, 1.0);
+vec4 color_227 = vec4(0.906084, 0.035470, 0.060852, 1.0);
+vec4 color_228 = vec4(0.840624, 0.042815, 0.273590, 1.0);
+vec4 color_229 = vec4(0.117437, 0.091038, 0.027623, 1.0);
+vec4 color_230 = vec4(0.637513, 0.744614, 0.686771, 1.0);
+vec4 color_231 = vec4(0.845623, 0.663016, 0.389702, 1.0);
+vec4 color_232 = vec4(0.631063, 0.969595, 0.641603, 1.0);
+vec4 color_233 = vec4(0.243092, 0.060184, 0.935166, 1.0);
+vec4 color_234 = vec4(0.590495, 0.349615, 0.605353, 1.0);
+vec4 color_235 = vec4(0.560258, 0.522172, 0.060805, 1.0);
+vec4 color_236 = vec4(0.353228, 0.412650, 0.199368, 1.0);
+vec4 color_237 = vec4(0.880105, 0.424120, 0.662386, 1.0);
+vec4 color_238 = vec4(0.713546, 0.743283, 0.721115, 1.0);
+vec4 color_239 = vec4(0.752209, 0.251581, 0.976404, 1.0);
+vec4 color_240 = vec4(0.151010, 0.918647, 0.854569, 1.0);
+vec4 color_241 = vec4(0.852164, 0.052811, 0.091218, 1.0);
+vec4 color_242 = vec4(0.813056, 0.469167, 0.370253, 1.0);
+vec4 color_243 = vec4(0.984687, 0.040118, 0.531465, 1.0);
+vec4 color_244 = vec4(0.443350, 0.128203, 0.395188, 1.0);
+vec4 color_245 = vec4(0.707647, 0.882316, 0.024620, 1.0);
+vec4 color_246 = vec4(0.524510, 0.090377, 0.800393, 1.0);
+vec4 color_247 = vec4(0.085785, 0.034193, 0.384236, 1.0);
+vec4 color_248 = vec4(0.732606, 0.313207, 0.130005, 1.0);
+vec4 color_249 = vec4(0.794572, 0.806919, 0.855860, 1.0);
+vec4 color_250 = vec4(0.303744, 0.424830, 0.245390, 1.0);
+vec4 color_251 = vec4(0.557177, 0.330107, 0.338663, 1.0);
+vec4 color_252 = vec4(0.783621, 0.956296, 0.584140, 1.0);
+vec4 color_253 = vec4(0.104688, 0.652575, 0.448612, 1.0);
+vec4 color_254 = vec4(0.988031, 0.719381, 0.834786, 1.0);
+vec4 color_255 = vec4(0.701286, 0.535619, 0.896818, 1.0);
+vec4 color_256 = vec4(0.831617, 0.291326, 0.157032, 1.0);
+vec4 color_257 = vec4(0.370352, 0.521078, 0.097380, 1.0);
+vec4 color_258 = vec4(0.345379, 0.574906, 0.043575, 1.0);
+vec4 color_259 = vec4(0.814949, 0.651117, 0.313650, 1.0);
+vec4 color_260 = vec4(0.298321, 0.352616, 0.325289, 1.0);
+vec4 color_261 = vec4(0.748514, 0.501057, 0.526128, 1.0);
+vec4 color_262 = vec4(0.148756, 0.914418, 0.325573, 1.0);
+vec4 color_263 = vec4(0.327564, 0.068846, 0.979412, 1.0);
+vec4 color_264 = vec4(0.479698, 0.912885, 0.927617, 1.0);
+vec4 color_265 = vec4(0.969752, 0.815629, 0.925443, 1.0);
+vec4 color_266 = vec4(0.922289, 0.801368, 0.134581, 1.0);
+vec4 color_267 = vec4(0.523712, 0.575604, 0.992498, 1.0);
+vec4 color_268 = vec4(0.783949, 0.702916, 0.746649, 1.0);
+vec4 color_269 = vec4(0.361578, 0.942314, 0.643501, 1.0);
+vec4 color_270 = vec4(0.402575, 0.464572, 0.979755, 1.0);
+vec4 color_271 = vec4(0.532128, 0.167798, 0.148355, 1.0);
+vec4 color_272 = vec4(0.687242, 0.562776, 0.906806, 1.0);
+vec4 color_273 = vec4(0.184600, 0.411109, 0.727960, 1.0);
+vec4 color_274 = vec4(0.050105, 0.099222, 0.545708, 1.0);
+vec4 color_275 = vec4(0.265729, 0.106938, 0.261698, 1.0);
+vec4 color_276 = vec4(0.632141, 0.526377, 0.078497, 1.0);
+vec4 color_277 = vec4(0.072811, 0.850627, 0.643239, 1.0);
+vec4 color_278 = vec4(0.173367, 0.861834, 0.021849, 1.0);
+vec4 color_279 = vec4(0.368105, 0.847630, 0.710278, 1.0);
+vec4 color_280 = vec4(0.283752, 0.891281, 0.598078, 1.0);
+vec4 color_281 = vec4(0.865493, 0.892793, 0.425444, 1.0);
+vec4 color_282 = vec4(0.675600, 0.544476, 0.944735, 1.0);
+vec4 color_283 = vec4(0.798161, 0.725819, 0.814032, 1.0);
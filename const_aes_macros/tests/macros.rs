@@ -0,0 +1,48 @@
+use const_aes::{encrypt_bytes, encrypt_str, include_encrypted, EncryptedFile};
+
+macro_rules! forward {
+    ($str:literal) => {
+        encrypt_str!($str)
+    };
+}
+
+#[test]
+fn test_encrypt_str() {
+    assert_eq!(encrypt_str!("This is a top secret message"), "This is a top secret message");
+    assert_eq!(encrypt_str!(""), "");
+    assert_eq!(encrypt_str!("exactly 16 bytes"), "exactly 16 bytes");
+    assert_eq!(encrypt_str!("héllo \u{1F980} wörld\n"), "héllo \u{1F980} wörld\n");
+    assert_eq!(encrypt_str!(r#"raw "string""#), r#"raw "string""#);
+}
+
+#[test]
+fn test_encrypt_bytes() {
+    assert_eq!(encrypt_bytes!(b"\xde\xad\xbe\xef"), vec![0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(encrypt_bytes!(b""), Vec::<u8>::new());
+}
+
+#[test]
+fn test_encrypt_str_forwarded_literal() {
+    assert_eq!(forward!("through macro_rules"), "through macro_rules");
+}
+
+static CONFIG: EncryptedFile = include_encrypted!("tests/fixtures/config.toml");
+
+#[test]
+fn test_include_encrypted() {
+    let expected = include_bytes!("fixtures/config.toml");
+
+    assert_eq!(CONFIG.decrypt(), expected);
+    assert_eq!(CONFIG.cipher_len(), (expected.len() / 16 + 1) * 16);
+    assert_eq!(include_encrypted!("tests/fixtures/config.toml").decrypt(), expected);
+}
+
+#[test]
+fn test_include_encrypted_large_file() {
+    //
+    // multi KiB files used to exceed the const evaluation limit
+    //
+    static SHADER: EncryptedFile = include_encrypted!("tests/fixtures/shader.glsl");
+
+    assert_eq!(SHADER.decrypt(), include_bytes!("fixtures/shader.glsl"));
+}
@@ -0,0 +1,64 @@
+//!
+//! Random bytes for the keys and ivs generated by `const_aes_macros` and the `build` feature
+//! of `const_aes`, shared so that both work on every platform the standard library supports.
+//!
+
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// random bytes for a key or an iv
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    fill(&mut bytes);
+
+    bytes
+}
+
+///
+/// fill the buffer with random bytes.
+///
+/// The OS generator is used when available, otherwise the bytes come from the randomly
+/// keyed hasher of the standard library which is seeded by the OS as well.
+///
+pub fn fill(bytes: &mut [u8]) {
+    if let Ok(mut urandom) = File::open("/dev/urandom") {
+        if urandom.read_exact(bytes).is_ok() {
+            return;
+        }
+    }
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    for chunk in bytes.chunks_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        hasher.write_u128(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos())
+                .unwrap_or_default()
+        );
+
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes()[..chunk.len()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_bytes() {
+        assert_ne!(random_bytes::<16>(), random_bytes::<16>());
+
+        let mut bytes = [0u8; 37];
+        fill(&mut bytes);
+
+        assert_ne!(bytes, [0u8; 37]);
+    }
+}
@@ -0,0 +1,12 @@
+//!
+//! Same as the main example but with the key and iv generated at each build by the macros.
+//!
+//! Run it with `cargo run --example macros --features macros`.
+//!
+
+use const_aes::{encrypt_bytes, encrypt_str};
+
+fn main(){
+    println!("Top secret: {}", encrypt_str!("This is a top secret message"));
+    println!("Top secret bytes: {:x?}", encrypt_bytes!(b"\xde\xad\xbe\xef"));
+}
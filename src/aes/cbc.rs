@@ -1,175 +1,475 @@
-use crate::Aes128CBC;
-use crate::utils::decrypt::{decrypt_block, remove_pkcs7_padding};
-use crate::utils::encrypt::{block_length_with_pkcs7_padding, encrypt_block, pkcs7_byte};
-use crate::utils::key_schedule;
-
-impl Aes128CBC {
-    pub const fn new(key: [u8; 16], iv: [u8; 16]) -> Self {
-        Self {
-            key,
-            iv,
+use crate::{Aes128CBC, Aes192CBC, Aes256CBC, CbcDecryptor, CbcEncryptor, CtsVariant, Error, PlainBuf, RoundKeys};
+use crate::padding::{Padding, PaddingScheme};
+use crate::aes::ecb::{read_block, write_block};
+use crate::utils::decrypt::equivalent_decrypt_block;
+use crate::utils::encrypt::encrypt_block;
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+/// implement the CBC contexts, which only differ by their key size
+macro_rules! impl_cbc {
+    ($name:ident, $round_keys:literal, $key_length:literal) => {
+        impl $name {
+            pub const fn new(key: [u8; $key_length], iv: [u8; 16]) -> Self {
+                Self::with_round_keys(RoundKeys::<$round_keys>::new(key), iv)
+            }
         }
-    }
 
-    pub const fn encrypt<const PLAIN_LENGTH: usize, const CIPHER_LENGTH: usize>(
-        &self,
-        plain: &[u8; PLAIN_LENGTH],
-    ) -> [u8; CIPHER_LENGTH] {
+        impl<P: Padding> $name<P> {
+            pub const fn with_padding(key: [u8; $key_length], iv: [u8; 16], _padding: P) -> Self {
+                Self::with_round_keys(RoundKeys::<$round_keys>::new(key), iv)
+            }
 
-        //
-        // check block size
-        //
-        if CIPHER_LENGTH != block_length_with_pkcs7_padding(PLAIN_LENGTH) {
-            panic!("Incorrect cipher length");
-        }
+            pub const fn with_round_keys(keys: RoundKeys<$round_keys>, iv: [u8; 16]) -> Self {
+                Self {
+                    keys,
+                    iv,
+                    padding: PhantomData,
+                }
+            }
 
-        //
-        // init
-        //
-        let mut cipher = [0u8; CIPHER_LENGTH];
-        let keys = key_schedule(&self.key);
+            /// start encrypting data fed in several parts
+            pub const fn encryptor(&self) -> CbcEncryptor<$round_keys, P> {
+                CbcEncryptor::new(self.keys, self.iv)
+            }
 
-        //
-        // copy plain into cipher
-        //
-        let mut i = 0;
-        while i < PLAIN_LENGTH {
-            cipher[i] = plain[i];
-            i += 1;
-        }
+            /// start decrypting data fed in several parts
+            pub const fn decryptor(&self) -> CbcDecryptor<$round_keys, P> {
+                CbcDecryptor::new(self.keys, self.iv)
+            }
 
-        //
-        // add pkcs7 bytes
-        //
-        let pkcs7 = pkcs7_byte(PLAIN_LENGTH);
-        while i < CIPHER_LENGTH {
-            cipher[i] = pkcs7;
+            pub const fn encrypt<const PLAIN_LENGTH: usize, const CIPHER_LENGTH: usize>(
+                &self,
+                plain: &[u8; PLAIN_LENGTH],
+            ) -> [u8; CIPHER_LENGTH] {
+                encrypt(&self.keys, P::SCHEME, &self.iv, plain)
+            }
 
-            i += 1;
-        }
+            /// decrypt and remove the padding, panics if the cipher is invalid
+            #[cfg(feature = "alloc")]
+            pub fn decrypt<const CIPHER_LENGTH: usize>(
+                &self,
+                cipher: &[u8; CIPHER_LENGTH]
+            ) -> Vec<u8> {
+                match self.try_decrypt(cipher) {
+                    Ok(plain) => plain,
+                    Err(err) => panic!("{}", err),
+                }
+            }
 
-        //
-        // convert to 16 bytes blocks and encrypt the blocks
-        //
-        i = 0;
-        let mut block = [0u8; 16];
-        let mut j = 0;
+            #[cfg(feature = "alloc")]
+            pub fn try_decrypt<const CIPHER_LENGTH: usize>(
+                &self,
+                cipher: &[u8; CIPHER_LENGTH]
+            ) -> Result<Vec<u8>, Error> {
+                self.decrypt_to_array(cipher).map(|plain| plain.as_slice().to_vec())
+            }
 
-        while i < CIPHER_LENGTH {
+            /// decrypt without allocating, usable at compile time
+            pub const fn decrypt_to_array<const CIPHER_LENGTH: usize>(
+                &self,
+                cipher: &[u8; CIPHER_LENGTH]
+            ) -> Result<PlainBuf<CIPHER_LENGTH>, Error> {
+                decrypt_to_array(&self.keys, P::SCHEME, &self.iv, cipher)
+            }
 
-            block[j] = cipher[i];
+            /// encrypt with ciphertext stealing, the cipher is as long as the plain text of at least 16 bytes
+            pub const fn encrypt_cts<const LENGTH: usize>(
+                &self,
+                variant: CtsVariant,
+                plain: &[u8; LENGTH],
+            ) -> [u8; LENGTH] {
+                encrypt_cts(&self.keys, variant, &self.iv, plain)
+            }
 
-            if i % 16 == 15 {
+            pub const fn decrypt_cts<const LENGTH: usize>(
+                &self,
+                variant: CtsVariant,
+                cipher: &[u8; LENGTH],
+            ) -> [u8; LENGTH] {
+                decrypt_cts(&self.keys, variant, &self.iv, cipher)
+            }
 
-                //
-                // xor the plaintext with the previous block or iv
-                //
-                let mut k = 0;
-                while k < 16 {
-                    if i < 16 {
-                        block[k] ^= self.iv[k];
-                    } else {
-                        block[k] ^= cipher[i + k - 31];
-                    }
+            /// encrypt data whose length is only known at runtime, panics if unaligned data is not padded
+            #[cfg(feature = "alloc")]
+            pub fn encrypt_vec(&self, plain: &[u8]) -> Vec<u8> {
+                let mut buf = vec![0u8; P::SCHEME.padded_length(plain.len())];
+                buf[..plain.len()].copy_from_slice(plain);
 
-                    k += 1;
+                match encrypt_in_place(&self.keys, P::SCHEME, &self.iv, &mut buf, plain.len()) {
+                    Ok(_) => buf,
+                    Err(err) => panic!("{}", err),
                 }
+            }
+
+            #[cfg(feature = "alloc")]
+            pub fn decrypt_slice(&self, cipher: &[u8]) -> Result<Vec<u8>, Error> {
+                let mut buf = cipher.to_vec();
+                let length = decrypt_in_place(&self.keys, P::SCHEME, &self.iv, &mut buf)?;
+
+                buf.truncate(length);
+                Ok(buf)
+            }
 
-                //
-                // encrypt that block
-                //
-                let block_enc = encrypt_block(&block, &keys);
-
-                //
-                // copy the bytes of that block
-                //
-                let mut k = 0;
-                while k < 16 {
-                    cipher[i - 15 + k] = block_enc[k];
-                    k += 1;
+            ///
+            /// pad and encrypt the first `msg_len` bytes of buf, returns the cipher.
+            ///
+            /// buf must have room for the padding, see `PaddingScheme::padded_length`.
+            ///
+            pub const fn encrypt_in_place<'a>(&self, buf: &'a mut [u8], msg_len: usize) -> Result<&'a [u8], Error> {
+                match encrypt_in_place(&self.keys, P::SCHEME, &self.iv, buf, msg_len) {
+                    Ok(length) => {
+                        let buf: &[u8] = buf;
+                        Ok(buf.split_at(length).0)
+                    }
+                    Err(err) => Err(err),
                 }
+            }
 
-                j = 0;
-            } else {
-                j += 1;
+            /// decrypt buf and remove the padding, returns the plain data
+            pub const fn decrypt_in_place<'a>(&self, buf: &'a mut [u8]) -> Result<&'a [u8], Error> {
+                match decrypt_in_place(&self.keys, P::SCHEME, &self.iv, buf) {
+                    Ok(length) => {
+                        let buf: &[u8] = buf;
+                        Ok(buf.split_at(length).0)
+                    }
+                    Err(err) => Err(err),
+                }
             }
+        }
+    };
+}
+
+impl_cbc!(Aes128CBC, 11, 16);
+impl_cbc!(Aes192CBC, 13, 24);
+impl_cbc!(Aes256CBC, 15, 32);
+
+pub(crate) const fn encrypt<const ROUND_KEYS: usize, const PLAIN_LENGTH: usize, const CIPHER_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    iv: &[u8; 16],
+    plain: &[u8; PLAIN_LENGTH],
+) -> [u8; CIPHER_LENGTH] {
+
+    //
+    // check block size
+    //
+    if CIPHER_LENGTH != padding.padded_length(PLAIN_LENGTH) || !CIPHER_LENGTH.is_multiple_of(16) {
+        panic!("Incorrect cipher length");
+    }
+
+    //
+    // copy plain into cipher
+    //
+    let mut cipher = [0u8; CIPHER_LENGTH];
+
+    let mut i = 0;
+    while i < PLAIN_LENGTH {
+        cipher[i] = plain[i];
+        i += 1;
+    }
 
-            i += 1;
+    match encrypt_in_place(keys, padding, iv, &mut cipher, PLAIN_LENGTH) {
+        Ok(_) => cipher,
+        Err(_) => panic!("Incorrect cipher length"),
+    }
+}
+
+pub(crate) const fn decrypt_to_array<const ROUND_KEYS: usize, const CIPHER_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    iv: &[u8; 16],
+    cipher: &[u8; CIPHER_LENGTH]
+) -> Result<PlainBuf<CIPHER_LENGTH>, Error> {
+    let mut plain = *cipher;
+
+    match decrypt_in_place(keys, padding, iv, &mut plain) {
+        Ok(length) => Ok(PlainBuf::new(plain, length)),
+        Err(err) => Err(err),
+    }
+}
+
+/// pad the first `length` bytes of buf and encrypt them, returns the cipher length
+pub(crate) const fn encrypt_in_place<const ROUND_KEYS: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    iv: &[u8; 16],
+    buf: &mut [u8],
+    length: usize,
+) -> Result<usize, Error> {
+
+    //
+    // check the buffer has room for the padding, without padding the data must be block aligned
+    //
+    let cipher_length = padding.padded_length(length);
+
+    if cipher_length > buf.len() || !cipher_length.is_multiple_of(16) {
+        return Err(Error::InvalidLength);
+    }
+
+    let (cipher, _) = buf.split_at_mut(cipher_length);
+
+    //
+    // add the padding bytes
+    //
+    padding.pad(cipher, length);
+
+    let mut prev = *iv;
+    encrypt_blocks(keys, &mut prev, cipher);
+
+    Ok(cipher_length)
+}
+
+/// decrypt buf and check the padding, returns the plain length
+pub(crate) const fn decrypt_in_place<const ROUND_KEYS: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    iv: &[u8; 16],
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+
+    //
+    // check cipher length
+    //
+    if !buf.len().is_multiple_of(16) {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut prev = *iv;
+    decrypt_blocks(keys, &mut prev, buf);
+
+    //
+    // remove the padding
+    //
+    padding.unpadded_length(buf)
+}
+
+///
+/// encrypt block aligned data in place.
+///
+/// Each block is xored with the previous cipher block, starting with prev which is left
+/// holding the last cipher block so that the chaining can be carried on.
+///
+pub(crate) const fn encrypt_blocks<const ROUND_KEYS: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    prev: &mut [u8; 16],
+    data: &mut [u8],
+) {
+    let mut i = 0;
+    while i < data.len() {
+        let mut block = read_block(data, i);
+
+        let mut k = 0;
+        while k < 16 {
+            block[k] ^= prev[k];
+            k += 1;
         }
 
-        cipher
+        *prev = encrypt_block(&block, &keys.enc);
+        write_block(data, i, prev);
+
+        i += 16;
     }
+}
 
-    pub fn decrypt<const CIPHER_LENGTH: usize>(
-        &self,
-        cipher: &[u8; CIPHER_LENGTH]
-    ) -> Vec<u8> {
+/// decrypt block aligned data in place, prev is left holding the last cipher block
+pub(crate) const fn decrypt_blocks<const ROUND_KEYS: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    prev: &mut [u8; 16],
+    data: &mut [u8],
+) {
+    let mut i = 0;
+    while i < data.len() {
 
         //
-        // check cipher length
+        // the cipher block is kept since it is overwritten
         //
-        if CIPHER_LENGTH % 16 != 0 {
-            panic!("Incorrect cipher length");
+        let block = read_block(data, i);
+        let mut block_dec = equivalent_decrypt_block(&block, &keys.dec);
+
+        let mut k = 0;
+        while k < 16 {
+            block_dec[k] ^= prev[k];
+            k += 1;
         }
 
-        //
-        // init
-        //
-        let mut plain = [0u8; CIPHER_LENGTH];
-        let keys = key_schedule(&self.key);
+        write_block(data, i, &block_dec);
+        *prev = block;
 
-        //
-        // convert to 16 bytes blocks and encrypt the blocks
-        //
-        let mut i = 0;
-        let mut block = [0u8; 16];
-        let mut j = 0;
+        i += 16;
+    }
+}
 
-        while i < CIPHER_LENGTH {
-            block[j] = cipher[i];
+pub(crate) const fn encrypt_cts<const ROUND_KEYS: usize, const LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    variant: CtsVariant,
+    iv: &[u8; 16],
+    plain: &[u8; LENGTH],
+) -> [u8; LENGTH] {
+
+    //
+    // nothing can be stolen from less than one block
+    //
+    if LENGTH < 16 {
+        panic!("Incorrect cipher length");
+    }
 
-            if i % 16 == 15 {
-                //
-                // encrypt that block
-                //
-                let block_enc = decrypt_block(&block, &keys);
+    //
+    // the last block holds 1 to 16 bytes, the blocks before it are chained as usual
+    //
+    let full = (LENGTH - 1) / 16;
+    let last = LENGTH - full * 16;
 
-                //
-                // that block was originally xored using iv or prev block
-                // and copy the bytes of that block
-                //
-                let mut k = 0;
-                while k < 16 {
+    let mut cipher = *plain;
+    let mut prev = *iv;
 
-                    if i < 16 {
-                        plain[i - 15 + k] = block_enc[k] ^ self.iv[k];
-                    } else {
-                        plain[i + k - 15] = block_enc[k] ^ cipher[i + k - 31];
-                    }
+    let (blocks, _) = cipher.split_at_mut(full * 16);
+    encrypt_blocks(keys, &mut prev, blocks);
 
-                    k += 1;
-                }
+    //
+    // the last block is padded with zeros before being chained
+    //
+    let mut block = [0u8; 16];
 
-                j = 0;
-            } else {
-                j += 1;
-            }
+    let mut k = 0;
+    while k < last {
+        block[k] = plain[full * 16 + k];
+        k += 1;
+    }
+
+    let next_to_last = prev;
+    encrypt_blocks(keys, &mut prev, &mut block);
+
+    if full == 0 {
+        write_block(&mut cipher, 0, &block);
+        return cipher;
+    }
 
-            i += 1;
+    //
+    // only the first bytes of the next to last block are kept, the others can be
+    // recovered from the last block when decrypting
+    //
+    let start = (full - 1) * 16;
+
+    if is_swapped(variant, last) {
+        write_block(&mut cipher, start, &block);
+        copy_stolen(&next_to_last, &mut cipher, start + 16, last);
+    } else {
+        copy_stolen(&next_to_last, &mut cipher, start, last);
+        write_block(&mut cipher, start + last, &block);
+    }
+
+    cipher
+}
+
+pub(crate) const fn decrypt_cts<const ROUND_KEYS: usize, const LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    variant: CtsVariant,
+    iv: &[u8; 16],
+    cipher: &[u8; LENGTH],
+) -> [u8; LENGTH] {
+
+    //
+    // nothing can be stolen from less than one block
+    //
+    if LENGTH < 16 {
+        panic!("Incorrect cipher length");
+    }
+
+    let full = (LENGTH - 1) / 16;
+    let last = LENGTH - full * 16;
+
+    let mut plain = *cipher;
+    let mut prev = *iv;
+
+    if full == 0 {
+        decrypt_blocks(keys, &mut prev, &mut plain);
+        return plain;
+    }
+
+    //
+    // the blocks before the last two are decrypted as usual
+    //
+    let start = (full - 1) * 16;
+
+    let (blocks, _) = plain.split_at_mut(start);
+    decrypt_blocks(keys, &mut prev, blocks);
+
+    //
+    // split the last two blocks in the truncated next to last block and the last block
+    //
+    let mut stolen = [0u8; 16];
+
+    let block = if is_swapped(variant, last) {
+        read_stolen(cipher, start + 16, last, &mut stolen);
+        read_block(cipher, start)
+    } else {
+        read_stolen(cipher, start, last, &mut stolen);
+        read_block(cipher, start + last)
+    };
+
+    //
+    // the last block decrypts to the zero padded plain text xored with the next to last
+    // block, its tail is the part of that block which was not kept
+    //
+    let block_dec = equivalent_decrypt_block(&block, &keys.dec);
+
+    let mut k = 0;
+    while k < 16 {
+        if k < last {
+            plain[full * 16 + k] = block_dec[k] ^ stolen[k];
+        } else {
+            stolen[k] = block_dec[k];
         }
 
-        //
-        // remove the padding
-        //
-        remove_pkcs7_padding(&plain)
+        k += 1;
+    }
+
+    //
+    // the next to last block is now complete and chained to the blocks before it
+    //
+    decrypt_blocks(keys, &mut prev, &mut stolen);
+    write_block(&mut plain, start, &stolen);
+
+    plain
+}
+
+/// copy the first `length` bytes of the block to data at start
+const fn copy_stolen(block: &[u8; 16], data: &mut [u8], start: usize, length: usize) {
+    let mut k = 0;
+    while k < length {
+        data[start + k] = block[k];
+        k += 1;
+    }
+}
+
+/// read `length` bytes of data at start into the first bytes of the block
+const fn read_stolen(data: &[u8], start: usize, length: usize, block: &mut [u8; 16]) {
+    let mut k = 0;
+    while k < length {
+        block[k] = data[start + k];
+        k += 1;
+    }
+}
+
+const fn is_swapped(variant: CtsVariant, last: usize) -> bool {
+    match variant {
+        CtsVariant::Cs1 => false,
+        CtsVariant::Cs2 => last != 16,
+        CtsVariant::Cs3 => true,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Aes128CBC;
+    use crate::{Aes128CBC, Aes192CBC, Aes256CBC, CtsVariant, Error, PlainBuf, RoundKeys};
+    use crate::utils::bytes_to_array;
+    use crate::padding::{AnsiX923, Iso10126, Iso7816, NoPadding, Padding, Pkcs7, ZeroPadding};
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_encrypt_decrypt_aes128_cbc(){
         const KEY: [u8; 16] = [
             0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
@@ -195,4 +495,342 @@ mod tests {
 
         assert_eq!(PLAIN, plain.as_slice());
     }
+
+    #[test]
+    fn test_encrypt_decrypt_aes192_cbc(){
+        const KEY: [u8; 24] = [
+            0x8e, 0x73, 0xb0, 0xf7, 0xda, 0x0e, 0x64, 0x52,
+            0xc8, 0x10, 0xf3, 0x2b, 0x80, 0x90, 0x79, 0xe5,
+            0x62, 0xf8, 0xea, 0xd2, 0x52, 0x2c, 0x6b, 0x7b
+        ];
+
+        const IV: [u8; 16] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F
+        ];
+
+        const PLAIN: [u8; 16] = [
+            0x6b, 0xc1, 0xbe, 0xe2,
+            0x2e, 0x40, 0x9f, 0x96,
+            0xe9, 0x3d, 0x7e, 0x11,
+            0x73, 0x93, 0x17, 0x2a
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0x4f, 0x02, 0x1d, 0xb2,
+            0x43, 0xbc, 0x63, 0x3d,
+            0x71, 0x78, 0x18, 0x3a,
+            0x9f, 0xa0, 0x71, 0xe8
+        ];
+
+        const CTX: Aes192CBC = Aes192CBC::new(KEY, IV);
+        const CIPHER: [u8; 32] = CTX.encrypt::<16, 32>(&PLAIN);
+
+        assert_eq!(CIPHER[..16], EXPECTED);
+
+        let plain = CTX.decrypt_to_array(&CIPHER).unwrap();
+
+        assert_eq!(PLAIN, plain.as_slice());
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes256_cbc(){
+        const KEY: [u8; 32] = [
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe,
+            0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81,
+            0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7,
+            0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4
+        ];
+
+        const IV: [u8; 16] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F
+        ];
+
+        const PLAIN: [u8; 16] = [
+            0x6b, 0xc1, 0xbe, 0xe2,
+            0x2e, 0x40, 0x9f, 0x96,
+            0xe9, 0x3d, 0x7e, 0x11,
+            0x73, 0x93, 0x17, 0x2a
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0xf5, 0x8c, 0x4c, 0x04,
+            0xd6, 0xe5, 0xf1, 0xba,
+            0x77, 0x9e, 0xab, 0xfb,
+            0x5f, 0x7b, 0xfb, 0xd6
+        ];
+
+        const CTX: Aes256CBC = Aes256CBC::new(KEY, IV);
+        const CIPHER: [u8; 32] = CTX.encrypt::<16, 32>(&PLAIN);
+
+        assert_eq!(CIPHER[..16], EXPECTED);
+
+        let plain = CTX.decrypt_to_array(&CIPHER).unwrap();
+
+        assert_eq!(PLAIN, plain.as_slice());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_try_decrypt_aes128_cbc_errors(){
+        const CTX: Aes128CBC = Aes128CBC::new([0x42; 16], [0x24; 16]);
+
+        assert_eq!(CTX.try_decrypt(&[0u8; 17]), Err(Error::InvalidLength));
+
+        //
+        // tampering with the last block breaks the padding
+        //
+        const CIPHER: [u8; 32] = CTX.encrypt::<20, 32>(&[0x41; 20]);
+
+        let mut cipher = CIPHER;
+        cipher[31] ^= 0x80;
+
+        assert!(CTX.try_decrypt(&cipher).is_err());
+        assert_eq!(CTX.try_decrypt(&CIPHER), Ok([0x41; 20].to_vec()));
+    }
+
+    #[test]
+    fn test_decrypt_to_array_aes128_cbc(){
+        const KEY: [u8; 16] = [0x42; 16];
+        const IV: [u8; 16] = [0x24; 16];
+        const PLAIN: [u8; 21] = *b"compile time decrypt!";
+
+        const CTX: Aes128CBC = Aes128CBC::new(KEY, IV);
+        const CIPHER: [u8; 32] = CTX.encrypt(&PLAIN);
+
+        //
+        // round trip checked by the compiler
+        //
+        const DECRYPTED: PlainBuf<32> = match CTX.decrypt_to_array(&CIPHER) {
+            Ok(plain) => plain,
+            Err(_) => panic!("decryption failed"),
+        };
+
+        const _: () = assert!(DECRYPTED.len() == PLAIN.len());
+
+        assert_eq!(DECRYPTED.as_slice(), PLAIN);
+        assert!(matches!(CTX.decrypt_to_array(&[0u8; 8]), Err(Error::InvalidLength)));
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_cbc_no_padding(){
+        const KEY: [u8; 16] = [
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+            0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c
+        ];
+
+        const IV: [u8; 16] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F
+        ];
+
+        const PLAIN: [u8; 64] = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96,
+            0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+            0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c,
+            0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+            0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11,
+            0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+            0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17,
+            0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10
+        ];
+
+        const EXPECTED: [u8; 64] = [
+            0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46,
+            0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9, 0x19, 0x7d,
+            0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee,
+            0x95, 0xdb, 0x11, 0x3a, 0x91, 0x76, 0x78, 0xb2,
+            0x73, 0xbe, 0xd6, 0xb8, 0xe3, 0xc1, 0x74, 0x3b,
+            0x71, 0x16, 0xe6, 0x9e, 0x22, 0x22, 0x95, 0x16,
+            0x3f, 0xf1, 0xca, 0xa1, 0x68, 0x1f, 0xac, 0x09,
+            0x12, 0x0e, 0xca, 0x30, 0x75, 0x86, 0xe1, 0xa7
+        ];
+
+        const CTX: Aes128CBC<NoPadding> = Aes128CBC::with_padding(KEY, IV, NoPadding);
+        const CIPHER: [u8; 64] = CTX.encrypt(&PLAIN);
+
+        assert_eq!(CIPHER, EXPECTED);
+        assert_eq!(CTX.decrypt_to_array(&CIPHER).unwrap().as_slice(), PLAIN);
+
+        //
+        // round keys shared with another context keep the padding of the new one
+        //
+        const SHARED: Aes128CBC<NoPadding> = Aes128CBC::with_round_keys(RoundKeys::<11>::new(KEY), IV);
+        assert_eq!(SHARED.encrypt(&PLAIN), EXPECTED);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_cbc_paddings(){
+        const KEY: [u8; 16] = [0x42; 16];
+        const IV: [u8; 16] = [0x24; 16];
+        const PLAIN: [u8; 21] = *b"padded with a scheme!";
+
+        const fn round_trip<P: Padding>(padding: P) -> bool {
+            let ctx = Aes128CBC::with_padding(KEY, IV, padding);
+            let cipher: [u8; 32] = ctx.encrypt(&PLAIN);
+
+            match ctx.decrypt_to_array(&cipher) {
+                Ok(plain) => plain.len() == PLAIN.len(),
+                Err(_) => false,
+            }
+        }
+
+        const _: () = assert!(round_trip(Pkcs7));
+        const _: () = assert!(round_trip(Iso7816));
+        const _: () = assert!(round_trip(AnsiX923));
+        const _: () = assert!(round_trip(Iso10126));
+        const _: () = assert!(round_trip(ZeroPadding));
+
+        //
+        // the padding is checked with the scheme of the context
+        //
+        const ISO: Aes128CBC<Iso7816> = Aes128CBC::with_padding(KEY, IV, Iso7816);
+        const CIPHER: [u8; 32] = Aes128CBC::new(KEY, IV).encrypt(&PLAIN);
+
+        assert_eq!(ISO.decrypt_to_array(&CIPHER).unwrap_err(), Error::InconsistentPadding);
+    }
+
+    //
+    // RFC 3962 appendix B, CBC-CS3 with a zero iv
+    //
+    const CTS_KEY: [u8; 16] = [
+        0x63, 0x68, 0x69, 0x63, 0x6b, 0x65, 0x6e, 0x20,
+        0x74, 0x65, 0x72, 0x69, 0x79, 0x61, 0x6b, 0x69
+    ];
+
+    const CTS_PLAIN: [u8; 64] = *b"I would like the General Gau's Chicken, please, and wonton soup.";
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_cbc_cs3(){
+        const EXPECTED_17: [u8; 17] = [
+            0xc6, 0x35, 0x35, 0x68, 0xf2, 0xbf, 0x8c, 0xb4,
+            0xd8, 0xa5, 0x80, 0x36, 0x2d, 0xa7, 0xff, 0x7f,
+            0x97
+        ];
+
+        const EXPECTED_32: [u8; 32] = [
+            0x39, 0x31, 0x25, 0x23, 0xa7, 0x86, 0x62, 0xd5,
+            0xbe, 0x7f, 0xcb, 0xcc, 0x98, 0xeb, 0xf5, 0xa8,
+            0x97, 0x68, 0x72, 0x68, 0xd6, 0xec, 0xcc, 0xc0,
+            0xc0, 0x7b, 0x25, 0xe2, 0x5e, 0xcf, 0xe5, 0x84
+        ];
+
+        const EXPECTED_47: [u8; 47] = [
+            0x97, 0x68, 0x72, 0x68, 0xd6, 0xec, 0xcc, 0xc0,
+            0xc0, 0x7b, 0x25, 0xe2, 0x5e, 0xcf, 0xe5, 0x84,
+            0xb3, 0xff, 0xfd, 0x94, 0x0c, 0x16, 0xa1, 0x8c,
+            0x1b, 0x55, 0x49, 0xd2, 0xf8, 0x38, 0x02, 0x9e,
+            0x39, 0x31, 0x25, 0x23, 0xa7, 0x86, 0x62, 0xd5,
+            0xbe, 0x7f, 0xcb, 0xcc, 0x98, 0xeb, 0xf5
+        ];
+
+        const CTX: Aes128CBC = Aes128CBC::new(CTS_KEY, [0u8; 16]);
+
+        const PLAIN_17: [u8; 17] = bytes_to_array(CTS_PLAIN.split_at(17).0);
+        const PLAIN_47: [u8; 47] = bytes_to_array(CTS_PLAIN.split_at(47).0);
+        const PLAIN_32: [u8; 32] = bytes_to_array(CTS_PLAIN.split_at(32).0);
+
+        const CIPHER_17: [u8; 17] = CTX.encrypt_cts(CtsVariant::Cs3, &PLAIN_17);
+        const CIPHER_32: [u8; 32] = CTX.encrypt_cts(CtsVariant::Cs3, &PLAIN_32);
+        const CIPHER_47: [u8; 47] = CTX.encrypt_cts(CtsVariant::Cs3, &PLAIN_47);
+
+        assert_eq!(CIPHER_17, EXPECTED_17);
+        assert_eq!(CIPHER_32, EXPECTED_32);
+        assert_eq!(CIPHER_47, EXPECTED_47);
+
+        assert_eq!(CTX.decrypt_cts(CtsVariant::Cs3, &CIPHER_17), PLAIN_17);
+        assert_eq!(CTX.decrypt_cts(CtsVariant::Cs3, &CIPHER_32), PLAIN_32);
+        assert_eq!(CTX.decrypt_cts(CtsVariant::Cs3, &CIPHER_47), PLAIN_47);
+    }
+
+    #[test]
+    fn test_aes128_cbc_cts_variants(){
+        const CTX: Aes128CBC = Aes128CBC::new(CTS_KEY, [0u8; 16]);
+        const PLAIN_47: [u8; 47] = bytes_to_array(CTS_PLAIN.split_at(47).0);
+
+        //
+        // CS1 keeps the truncated next to last block in place, CS2 swaps unaligned data like CS3
+        //
+        let cs1 = CTX.encrypt_cts(CtsVariant::Cs1, &PLAIN_47);
+        let cs2 = CTX.encrypt_cts(CtsVariant::Cs2, &PLAIN_47);
+        let cs3 = CTX.encrypt_cts(CtsVariant::Cs3, &PLAIN_47);
+
+        assert_eq!(cs2, cs3);
+        assert_eq!(cs1[..16], cs3[..16]);
+        assert_eq!(cs1[16..31], cs3[32..]);
+        assert_eq!(cs1[31..], cs3[16..32]);
+
+        //
+        // aligned data is plain CBC for CS1 and CS2
+        //
+        let cbc = Aes128CBC::with_padding(CTS_KEY, [0u8; 16], NoPadding).encrypt::<64, 64>(&CTS_PLAIN);
+
+        assert_eq!(CTX.encrypt_cts(CtsVariant::Cs1, &CTS_PLAIN), cbc);
+        assert_eq!(CTX.encrypt_cts(CtsVariant::Cs2, &CTS_PLAIN), cbc);
+
+        for variant in [CtsVariant::Cs1, CtsVariant::Cs2, CtsVariant::Cs3] {
+            let cipher = Aes256CBC::new([7u8; 32], [9u8; 16]).encrypt_cts(variant, &PLAIN_47);
+            assert_eq!(Aes256CBC::new([7u8; 32], [9u8; 16]).decrypt_cts(variant, &cipher), PLAIN_47);
+
+            let cipher = CTX.encrypt_cts(variant, &CTS_PLAIN);
+            assert_eq!(CTX.decrypt_cts(variant, &cipher), CTS_PLAIN);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Incorrect cipher length")]
+    fn test_aes128_cbc_cts_short_input(){
+        Aes128CBC::new(CTS_KEY, [0u8; 16]).encrypt_cts(CtsVariant::Cs3, &[0u8; 15]);
+    }
+
+    #[test]
+    fn test_unaligned_aes128_cbc_no_padding(){
+        const CTX: Aes128CBC<NoPadding> = Aes128CBC::with_padding([0x2b; 16], [0x0f; 16], NoPadding);
+
+        let mut buf = [0u8; 32];
+        assert_eq!(CTX.encrypt_in_place(&mut buf, 5), Err(Error::InvalidLength));
+        assert_eq!(CTX.encrypt_in_place(&mut buf, 31), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "invalid cipher length")]
+    fn test_unaligned_aes128_cbc_no_padding_vec(){
+        Aes128CBC::with_padding([0x2b; 16], [0x0f; 16], NoPadding).encrypt_vec(b"hello");
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_in_place_aes128_cbc(){
+        const CTX: Aes128CBC = Aes128CBC::new([0x2b; 16], [0x0f; 16]);
+        const CIPHER: [u8; 48] = CTX.encrypt::<40, 48>(&[7u8; 40]);
+
+        let mut buf = [0u8; 64];
+        buf[..40].copy_from_slice(&[7u8; 40]);
+
+        assert_eq!(CTX.encrypt_in_place(&mut buf, 40), Ok(&CIPHER[..]));
+        assert_eq!(CTX.decrypt_in_place(&mut buf[..48]), Ok(&[7u8; 40][..]));
+
+        //
+        // the buffer must have room for the padding and the cipher must be block aligned
+        //
+        assert_eq!(CTX.encrypt_in_place(&mut buf[..40], 40), Err(Error::InvalidLength));
+        assert_eq!(CTX.decrypt_in_place(&mut buf[..47]), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_encrypt_vec_decrypt_slice_aes192_cbc(){
+        let ctx = Aes192CBC::with_padding([0x8e; 24], [0x01; 16], Iso7816);
+
+        for length in [0, 1, 15, 16, 17, 100] {
+            let plain: alloc::vec::Vec<u8> = (0..length as u8).collect();
+            let cipher = ctx.encrypt_vec(&plain);
+
+            assert_eq!(cipher.len(), (length / 16 + 1) * 16);
+            assert_eq!(ctx.decrypt_slice(&cipher), Ok(plain));
+        }
+
+        assert_eq!(ctx.decrypt_slice(&[]), Err(Error::InvalidLength));
+    }
 }
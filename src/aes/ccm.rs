@@ -0,0 +1,343 @@
+use crate::{Aes128CCM, Error, RoundKeys};
+use crate::aes::ctr::{apply_keystream, Increment};
+use crate::utils::constant_time_eq;
+use crate::utils::encrypt::encrypt_block;
+
+impl<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> Aes128CCM<TAG_LENGTH, NONCE_LENGTH> {
+    /// panics unless the nonce has 7 to 13 bytes and the tag 0 or an even length from 4 to 16
+    pub const fn new(key: [u8; 16]) -> Self {
+        Self::with_round_keys(RoundKeys::<11>::new(key))
+    }
+
+    pub const fn with_round_keys(keys: RoundKeys<11>) -> Self {
+        if NONCE_LENGTH < 7 || NONCE_LENGTH > 13 {
+            panic!("Invalid nonce length");
+        }
+
+        if !matches!(TAG_LENGTH, 0 | 4 | 6 | 8 | 10 | 12 | 14 | 16) {
+            panic!("Invalid tag length");
+        }
+
+        Self {
+            keys,
+        }
+    }
+
+    /// encrypt and authenticate, returns the cipher and the tag
+    pub const fn encrypt<const LENGTH: usize>(
+        &self,
+        nonce: &[u8; NONCE_LENGTH],
+        aad: &[u8],
+        plain: &[u8; LENGTH],
+    ) -> ([u8; LENGTH], [u8; TAG_LENGTH]) {
+        if !length_fits::<NONCE_LENGTH>(LENGTH) {
+            panic!("Incorrect cipher length");
+        }
+
+        let keys = &self.keys.enc;
+        let mac = cbc_mac::<TAG_LENGTH, NONCE_LENGTH>(keys, nonce, aad, plain);
+
+        let mut cipher = *plain;
+        let counter = counter_block::<NONCE_LENGTH>(nonce, 1);
+        apply_keystream(keys, &counter, Increment::Counter128, &mut cipher);
+
+        (cipher, encrypt_mac(keys, nonce, &mac))
+    }
+
+    /// check the tag and decrypt, nothing is returned unless the cipher is authentic
+    pub const fn decrypt<const LENGTH: usize>(
+        &self,
+        nonce: &[u8; NONCE_LENGTH],
+        aad: &[u8],
+        cipher: &[u8; LENGTH],
+        tag: &[u8; TAG_LENGTH],
+    ) -> Result<[u8; LENGTH], Error> {
+        if !length_fits::<NONCE_LENGTH>(LENGTH) {
+            return Err(Error::InvalidLength);
+        }
+
+        let keys = &self.keys.enc;
+
+        //
+        // the tag is computed over the plain text so decrypt first
+        //
+        let mut plain = *cipher;
+        let counter = counter_block::<NONCE_LENGTH>(nonce, 1);
+        apply_keystream(keys, &counter, Increment::Counter128, &mut plain);
+
+        let mac = cbc_mac::<TAG_LENGTH, NONCE_LENGTH>(keys, nonce, aad, &plain);
+        let expected = encrypt_mac::<TAG_LENGTH, NONCE_LENGTH>(keys, nonce, &mac);
+
+        if !constant_time_eq(&expected, tag) {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        Ok(plain)
+    }
+}
+
+/// counter block A_i made of the flags, the nonce and the counter on the remaining bytes
+const fn counter_block<const NONCE_LENGTH: usize>(nonce: &[u8; NONCE_LENGTH], counter: usize) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[0] = (14 - NONCE_LENGTH) as u8;
+
+    let mut i = 0;
+    while i < NONCE_LENGTH {
+        block[i + 1] = nonce[i];
+        i += 1;
+    }
+
+    write_be(&mut block, counter as u64, NONCE_LENGTH + 1);
+    block
+}
+
+/// write value big endian in the bytes of block from start to the end
+const fn write_be(block: &mut [u8; 16], value: u64, start: usize) {
+    let bytes = value.to_be_bytes();
+
+    let mut i = 16;
+    let mut j = 8;
+    while i > start && j > 0 {
+        i -= 1;
+        j -= 1;
+        block[i] = bytes[j];
+    }
+}
+
+/// encrypt the CBC-MAC with the keystream block S_0 to get the tag
+const fn encrypt_mac<const TAG_LENGTH: usize, const NONCE_LENGTH: usize>(
+    keys: &[[u8; 16]; 11],
+    nonce: &[u8; NONCE_LENGTH],
+    mac: &[u8; 16],
+) -> [u8; TAG_LENGTH] {
+    let s0 = encrypt_block(&counter_block::<NONCE_LENGTH>(nonce, 0), keys);
+
+    let mut tag = [0u8; TAG_LENGTH];
+    let mut i = 0;
+    while i < TAG_LENGTH {
+        tag[i] = mac[i] ^ s0[i];
+        i += 1;
+    }
+
+    tag
+}
+
+/// whether the message length fits in the bytes left by the nonce
+const fn length_fits<const NONCE_LENGTH: usize>(length: usize) -> bool {
+    let length_bytes = 15 - NONCE_LENGTH;
+
+    length_bytes >= 8 || (length as u64) >> (8 * length_bytes) == 0
+}
+
+///
+/// CBC-MAC over B_0, the encoded aad and the plain text, each zero padded to a block.
+///
+/// The callers check that the plain text length fits with `length_fits`.
+///
+const fn cbc_mac<const TAG_LENGTH: usize, const NONCE_LENGTH: usize>(
+    keys: &[[u8; 16]; 11],
+    nonce: &[u8; NONCE_LENGTH],
+    aad: &[u8],
+    plain: &[u8],
+) -> [u8; 16] {
+
+    //
+    // B_0 holds the flags, the nonce and the message length, CCM* encodes a zero tag length as 0
+    //
+    let mut b0 = counter_block::<NONCE_LENGTH>(nonce, plain.len());
+
+    if TAG_LENGTH > 0 {
+        b0[0] |= (((TAG_LENGTH - 2) / 2) as u8) << 3;
+    }
+
+    if !aad.is_empty() {
+        b0[0] |= 0x40;
+    }
+
+    let mut mac = encrypt_block(&b0, keys);
+    let mut position = 0;
+
+    //
+    // the aad is prefixed with its length on 2, 6 or 10 bytes
+    //
+    if !aad.is_empty() {
+        let length = aad.len() as u64;
+
+        if length < 0xFF00 {
+            absorb(keys, &mut mac, &mut position, &(length as u16).to_be_bytes());
+        } else if length <= u32::MAX as u64 {
+            absorb(keys, &mut mac, &mut position, &[0xFF, 0xFE]);
+            absorb(keys, &mut mac, &mut position, &(length as u32).to_be_bytes());
+        } else {
+            absorb(keys, &mut mac, &mut position, &[0xFF, 0xFF]);
+            absorb(keys, &mut mac, &mut position, &length.to_be_bytes());
+        }
+
+        absorb(keys, &mut mac, &mut position, aad);
+        pad(keys, &mut mac, &mut position);
+    }
+
+    absorb(keys, &mut mac, &mut position, plain);
+    pad(keys, &mut mac, &mut position);
+
+    mac
+}
+
+/// xor data into the CBC-MAC state, encrypting it at each block boundary
+const fn absorb(keys: &[[u8; 16]; 11], mac: &mut [u8; 16], position: &mut usize, data: &[u8]) {
+    let mut i = 0;
+
+    while i < data.len() {
+        mac[*position] ^= data[i];
+        *position += 1;
+
+        if *position == 16 {
+            *mac = encrypt_block(mac, keys);
+            *position = 0;
+        }
+
+        i += 1;
+    }
+}
+
+/// complete a partial block with zeros
+const fn pad(keys: &[[u8; 16]; 11], mac: &mut [u8; 16], position: &mut usize) {
+    if *position > 0 {
+        *mac = encrypt_block(mac, keys);
+        *position = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128CCM, Aes128CTR, Error};
+
+    #[test]
+    fn test_aes128_ccm_rfc3610(){
+        //
+        // RFC 3610 packet vector #1
+        //
+        const KEY: [u8; 16] = [
+            0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7,
+            0xc8, 0xc9, 0xca, 0xcb, 0xcc, 0xcd, 0xce, 0xcf
+        ];
+
+        const NONCE: [u8; 13] = [
+            0x00, 0x00, 0x00, 0x03, 0x02, 0x01, 0x00, 0xa0,
+            0xa1, 0xa2, 0xa3, 0xa4, 0xa5
+        ];
+
+        const AAD: [u8; 8] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+
+        const PLAIN: [u8; 23] = [
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e
+        ];
+
+        const EXPECTED: [u8; 23] = [
+            0x58, 0x8c, 0x97, 0x9a, 0x61, 0xc6, 0x63, 0xd2,
+            0xf0, 0x66, 0xd0, 0xc2, 0xc0, 0xf9, 0x89, 0x80,
+            0x6d, 0x5f, 0x6b, 0x61, 0xda, 0xc3, 0x84
+        ];
+
+        const EXPECTED_TAG: [u8; 8] = [0x17, 0xe8, 0xd1, 0x2c, 0xfd, 0xf9, 0x26, 0xe0];
+
+        const CTX: Aes128CCM<8, 13> = Aes128CCM::new(KEY);
+        const SEALED: ([u8; 23], [u8; 8]) = CTX.encrypt(&NONCE, &AAD, &PLAIN);
+
+        assert_eq!(SEALED.0, EXPECTED);
+        assert_eq!(SEALED.1, EXPECTED_TAG);
+        assert_eq!(CTX.decrypt(&NONCE, &AAD, &SEALED.0, &SEALED.1), Ok(PLAIN));
+
+        let mut cipher = SEALED.0;
+        cipher[22] ^= 1;
+
+        assert_eq!(CTX.decrypt(&NONCE, &AAD, &cipher, &SEALED.1), Err(Error::AuthenticationFailed));
+        assert_eq!(CTX.decrypt(&NONCE, &AAD[1..], &SEALED.0, &SEALED.1), Err(Error::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_aes128_ccm_sp800_38c(){
+        const KEY: [u8; 16] = [
+            0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47,
+            0x48, 0x49, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e, 0x4f
+        ];
+
+        const AAD: [u8; 16] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f
+        ];
+
+        const PLAIN: [u8; 16] = [
+            0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27,
+            0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f
+        ];
+
+        //
+        // example 1, 7 bytes nonce and 4 bytes tag
+        //
+        const CTX_1: Aes128CCM<4, 7> = Aes128CCM::new(KEY);
+        const NONCE_1: [u8; 7] = [0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16];
+        const SEALED_1: ([u8; 4], [u8; 4]) = CTX_1.encrypt(&NONCE_1, AAD.split_at(8).0, PLAIN.first_chunk().unwrap());
+
+        assert_eq!(SEALED_1, ([0x71, 0x62, 0x01, 0x5b], [0x4d, 0xac, 0x25, 0x5d]));
+
+        //
+        // example 2, 8 bytes nonce and 6 bytes tag
+        //
+        const CTX_2: Aes128CCM<6, 8> = Aes128CCM::new(KEY);
+        const NONCE_2: [u8; 8] = [0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17];
+        const SEALED_2: ([u8; 16], [u8; 6]) = CTX_2.encrypt(&NONCE_2, &AAD, &PLAIN);
+
+        assert_eq!(SEALED_2.0, [
+            0xd2, 0xa1, 0xf0, 0xe0, 0x51, 0xea, 0x5f, 0x62,
+            0x08, 0x1a, 0x77, 0x92, 0x07, 0x3d, 0x59, 0x3d
+        ]);
+        assert_eq!(SEALED_2.1, [0x1f, 0xc6, 0x4f, 0xbf, 0xac, 0xcd]);
+        assert_eq!(CTX_2.decrypt(&NONCE_2, &AAD, &SEALED_2.0, &SEALED_2.1), Ok(PLAIN));
+    }
+
+    #[test]
+    fn test_aes128_ccm_star_no_tag(){
+        const KEY: [u8; 16] = [0x42; 16];
+        const NONCE: [u8; 13] = [0x24; 13];
+
+        const CTX: Aes128CCM<0, 13> = Aes128CCM::new(KEY);
+        const SEALED: ([u8; 20], [u8; 0]) = CTX.encrypt(&NONCE, &[], &[7u8; 20]);
+
+        //
+        // without a tag CCM* is CTR starting from the counter block A_1
+        //
+        let mut counter = [0u8; 16];
+        counter[0] = 1;
+        counter[1..14].copy_from_slice(&NONCE);
+        counter[15] = 1;
+
+        assert_eq!(SEALED.0, Aes128CTR::new(KEY, counter).encrypt(&[7u8; 20]));
+        assert_eq!(CTX.decrypt(&NONCE, &[], &SEALED.0, &[]), Ok([7u8; 20]));
+    }
+
+    #[test]
+    fn test_aes128_ccm_message_too_long(){
+        //
+        // a 13 bytes nonce leaves 2 bytes for the message length
+        //
+        const CTX: Aes128CCM<8, 13> = Aes128CCM::new([0x42; 16]);
+
+        let cipher = [0u8; 0x10000];
+        assert_eq!(CTX.decrypt(&[0x24; 13], &[], &cipher, &[0u8; 8]), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    #[should_panic(expected = "Incorrect cipher length")]
+    fn test_aes128_ccm_encrypt_too_long(){
+        Aes128CCM::<8, 13>::new([0x42; 16]).encrypt(&[0x24; 13], &[], &[0u8; 0x10000]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid tag length")]
+    fn test_aes128_ccm_invalid_tag_length(){
+        Aes128CCM::<5, 13>::new([0u8; 16]);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{Aes128CFB, RoundKeys};
+use crate::utils::encrypt::encrypt_block;
+
+impl<const SEGMENT_BITS: usize> Aes128CFB<SEGMENT_BITS> {
+    /// panics unless the segment size is 1, 8 or 128 bits
+    pub const fn new(key: [u8; 16], iv: [u8; 16]) -> Self {
+        Self::with_round_keys(RoundKeys::<11>::new(key), iv)
+    }
+
+    /// panics unless the segment size is 1, 8 or 128 bits
+    pub const fn with_round_keys(keys: RoundKeys<11>, iv: [u8; 16]) -> Self {
+        if !matches!(SEGMENT_BITS, 1 | 8 | 128) {
+            panic!("Invalid segment size");
+        }
+
+        Self {
+            keys,
+            iv,
+        }
+    }
+
+    pub const fn encrypt<const LENGTH: usize>(
+        &self,
+        plain: &[u8; LENGTH],
+    ) -> [u8; LENGTH] {
+        let mut cipher = *plain;
+        self.encrypt_in_place(&mut cipher);
+
+        cipher
+    }
+
+    pub const fn decrypt<const LENGTH: usize>(
+        &self,
+        cipher: &[u8; LENGTH],
+    ) -> [u8; LENGTH] {
+        let mut plain = *cipher;
+        self.decrypt_in_place(&mut plain);
+
+        plain
+    }
+
+    pub const fn encrypt_in_place(&self, data: &mut [u8]) {
+        apply(&self.keys.enc, &self.iv, SEGMENT_BITS, data, false)
+    }
+
+    pub const fn decrypt_in_place(&self, data: &mut [u8]) {
+        apply(&self.keys.enc, &self.iv, SEGMENT_BITS, data, true)
+    }
+}
+
+///
+/// encrypt or decrypt data in place.
+///
+/// The shift register starts with the iv and is fed with the cipher segments, which are
+/// the input when decrypting and the output when encrypting.
+///
+pub(crate) const fn apply<const ROUND_KEYS: usize>(
+    keys: &[[u8; 16]; ROUND_KEYS],
+    iv: &[u8; 16],
+    segment_bits: usize,
+    data: &mut [u8],
+    decrypt: bool,
+) {
+    let mut register = *iv;
+    let mut output = [0u8; 16];
+
+    let mut i = 0;
+
+    while i < data.len() {
+        match segment_bits {
+
+            //
+            // the register is replaced by each cipher block, the last block may be partial
+            //
+            128 => {
+                if i % 16 == 0 {
+                    output = encrypt_block(&register, keys);
+                }
+
+                let result = data[i] ^ output[i % 16];
+                register[i % 16] = if decrypt { data[i] } else { result };
+                data[i] = result;
+            }
+
+            //
+            // one cipher byte is shifted into the register for each byte
+            //
+            8 => {
+                output = encrypt_block(&register, keys);
+
+                let result = data[i] ^ output[0];
+                shift_register(&mut register, 8, if decrypt { data[i] } else { result });
+                data[i] = result;
+            }
+
+            //
+            // one cipher bit is shifted into the register for each bit, most significant first
+            //
+            _ => {
+                let mut result = 0;
+
+                let mut bit = 8;
+                while bit > 0 {
+                    bit -= 1;
+                    output = encrypt_block(&register, keys);
+
+                    let input = (data[i] >> bit) & 1;
+                    let cipher_bit = input ^ (output[0] >> 7);
+
+                    result |= cipher_bit << bit;
+                    shift_register(&mut register, 1, if decrypt { input } else { cipher_bit });
+                }
+
+                data[i] = result;
+            }
+        }
+
+        i += 1;
+    }
+}
+
+/// shift the register left by 1 or 8 bits and append the bits of value
+const fn shift_register(register: &mut [u8; 16], bits: u32, value: u8) {
+    let mut k = 0;
+    while k < 15 {
+        register[k] = if bits == 8 {
+            register[k + 1]
+        } else {
+            (register[k] << 1) | (register[k + 1] >> 7)
+        };
+
+        k += 1;
+    }
+
+    register[15] = if bits == 8 { value } else { (register[15] << 1) | value };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Aes128CFB;
+
+    const KEY: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+        0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c
+    ];
+
+    const IV: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f
+    ];
+
+    const PLAIN: [u8; 64] = [
+        0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96,
+        0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+        0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c,
+        0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+        0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11,
+        0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+        0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17,
+        0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10
+    ];
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_cfb128(){
+        //
+        // SP 800-38A F.3.13
+        //
+        const EXPECTED: [u8; 64] = [
+            0x3b, 0x3f, 0xd9, 0x2e, 0xb7, 0x2d, 0xad, 0x20,
+            0x33, 0x34, 0x49, 0xf8, 0xe8, 0x3c, 0xfb, 0x4a,
+            0xc8, 0xa6, 0x45, 0x37, 0xa0, 0xb3, 0xa9, 0x3f,
+            0xcd, 0xe3, 0xcd, 0xad, 0x9f, 0x1c, 0xe5, 0x8b,
+            0x26, 0x75, 0x1f, 0x67, 0xa3, 0xcb, 0xb1, 0x40,
+            0xb1, 0x80, 0x8c, 0xf1, 0x87, 0xa4, 0xf4, 0xdf,
+            0xc0, 0x4b, 0x05, 0x35, 0x7c, 0x5d, 0x1c, 0x0e,
+            0xea, 0xc4, 0xc6, 0x6f, 0x9f, 0xf7, 0xf2, 0xe6
+        ];
+
+        const CTX: Aes128CFB<128> = Aes128CFB::new(KEY, IV);
+        const CIPHER: [u8; 64] = CTX.encrypt(&PLAIN);
+
+        assert_eq!(CIPHER, EXPECTED);
+        assert_eq!(CTX.decrypt(&CIPHER), PLAIN);
+
+        //
+        // the last segment may be partial
+        //
+        let mut data = [0u8; 37];
+        data.copy_from_slice(&PLAIN[..37]);
+        CTX.encrypt_in_place(&mut data);
+
+        assert_eq!(data, EXPECTED[..37]);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_cfb8(){
+        //
+        // SP 800-38A F.3.7
+        //
+        const EXPECTED: [u8; 18] = [
+            0x3b, 0x79, 0x42, 0x4c, 0x9c, 0x0d, 0xd4, 0x36,
+            0xba, 0xce, 0x9e, 0x0e, 0xd4, 0x58, 0x6a, 0x4f,
+            0x32, 0xb9
+        ];
+
+        const CTX: Aes128CFB<8> = Aes128CFB::new(KEY, IV);
+        const CIPHER: [u8; 18] = CTX.encrypt(PLAIN.first_chunk().unwrap());
+
+        assert_eq!(CIPHER, EXPECTED);
+        assert_eq!(CTX.decrypt(&CIPHER), PLAIN[..18]);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_cfb1(){
+        //
+        // SP 800-38A F.3.1, 16 segments of one bit
+        //
+        const CTX: Aes128CFB<1> = Aes128CFB::new(KEY, IV);
+        const CIPHER: [u8; 2] = CTX.encrypt(&[0x6b, 0xc1]);
+
+        assert_eq!(CIPHER, [0x68, 0xb3]);
+        assert_eq!(CTX.decrypt(&CIPHER), [0x6b, 0xc1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid segment size")]
+    fn test_aes128_cfb_invalid_segment(){
+        Aes128CFB::<64>::new(KEY, IV);
+    }
+}
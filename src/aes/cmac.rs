@@ -0,0 +1,212 @@
+use crate::{Aes128CMAC, CmacState, Error, RoundKeys};
+use crate::utils::constant_time_eq;
+use crate::utils::encrypt::encrypt_block;
+
+/// constant of the subkey doubling in GF(2^128)
+const RB: u128 = 0x87;
+
+impl Aes128CMAC {
+    pub const fn new(key: [u8; 16]) -> Self {
+        Self::with_round_keys(RoundKeys::<11>::new(key))
+    }
+
+    /// the subkeys K1 and K2 are derived once from the round keys
+    pub const fn with_round_keys(keys: RoundKeys<11>) -> Self {
+        let l = u128::from_be_bytes(encrypt_block(&[0u8; 16], &keys.enc));
+        let k1 = double(l);
+
+        Self {
+            keys,
+            k1: k1.to_be_bytes(),
+            k2: double(k1).to_be_bytes(),
+        }
+    }
+
+    /// tag of the whole message
+    pub const fn mac(&self, message: &[u8]) -> [u8; 16] {
+        let mut state = self.state();
+        state.update(message);
+
+        state.finalize()
+    }
+
+    /// check a tag truncated to at least 8 bytes in constant time
+    pub const fn verify(&self, message: &[u8], tag: &[u8]) -> Result<(), Error> {
+        if tag.len() < 8 || tag.len() > 16 {
+            return Err(Error::InvalidLength);
+        }
+
+        if constant_time_eq(&self.mac(message), tag) {
+            Ok(())
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+
+    /// start an incremental computation for messages fed in several parts
+    pub const fn state(&self) -> CmacState<'_> {
+        CmacState {
+            cmac: self,
+            state: [0u8; 16],
+            buffer: [0u8; 16],
+            buffered: 0,
+        }
+    }
+}
+
+impl CmacState<'_> {
+    pub const fn update(&mut self, data: &[u8]) {
+        let mut i = 0;
+
+        while i < data.len() {
+
+            //
+            // the last block gets the subkey treatment, so a full buffer is only
+            // chained once more data follows it
+            //
+            if self.buffered == 16 {
+                self.chain();
+            }
+
+            self.buffer[self.buffered] = data[i];
+            self.buffered += 1;
+            i += 1;
+        }
+    }
+
+    pub const fn finalize(mut self) -> [u8; 16] {
+
+        //
+        // a complete last block is xored with K1, a partial one is padded with 0x80 0x00..
+        // and xored with K2
+        //
+        let subkey = if self.buffered == 16 {
+            &self.cmac.k1
+        } else {
+            self.buffer[self.buffered] = 0x80;
+
+            let mut k = self.buffered + 1;
+            while k < 16 {
+                self.buffer[k] = 0;
+                k += 1;
+            }
+
+            &self.cmac.k2
+        };
+
+        let mut k = 0;
+        while k < 16 {
+            self.buffer[k] ^= subkey[k];
+            k += 1;
+        }
+
+        self.chain();
+        self.state
+    }
+
+    /// CBC-MAC step on the buffered block
+    const fn chain(&mut self) {
+        let mut k = 0;
+        while k < 16 {
+            self.state[k] ^= self.buffer[k];
+            k += 1;
+        }
+
+        self.state = encrypt_block(&self.state, &self.cmac.keys.enc);
+        self.buffered = 0;
+    }
+}
+
+/// multiplication by x in GF(2^128) with the CMAC polynomial
+pub(crate) const fn double(value: u128) -> u128 {
+    (value << 1) ^ (RB & 0u128.wrapping_sub(value >> 127))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128CMAC, Error};
+
+    //
+    // RFC 4493 section 4
+    //
+    const KEY: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+        0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c
+    ];
+
+    const MESSAGE: [u8; 64] = [
+        0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96,
+        0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+        0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c,
+        0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+        0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11,
+        0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+        0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17,
+        0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10
+    ];
+
+    const CTX: Aes128CMAC = Aes128CMAC::new(KEY);
+
+    #[test]
+    fn test_aes128_cmac_subkeys(){
+        assert_eq!(CTX.k1, [
+            0xfb, 0xee, 0xd6, 0x18, 0x35, 0x71, 0x33, 0x66,
+            0x7c, 0x85, 0xe0, 0x8f, 0x72, 0x36, 0xa8, 0xde
+        ]);
+
+        assert_eq!(CTX.k2, [
+            0xf7, 0xdd, 0xac, 0x30, 0x6a, 0xe2, 0x66, 0xcc,
+            0xf9, 0x0b, 0xc1, 0x1e, 0xe4, 0x6d, 0x51, 0x3b
+        ]);
+    }
+
+    #[test]
+    fn test_aes128_cmac(){
+        const TAG_0: [u8; 16] = CTX.mac(&[]);
+        const TAG_16: [u8; 16] = CTX.mac(MESSAGE.split_at(16).0);
+        const TAG_40: [u8; 16] = CTX.mac(MESSAGE.split_at(40).0);
+        const TAG_64: [u8; 16] = CTX.mac(&MESSAGE);
+
+        assert_eq!(TAG_0, [
+            0xbb, 0x1d, 0x69, 0x29, 0xe9, 0x59, 0x37, 0x28,
+            0x7f, 0xa3, 0x7d, 0x12, 0x9b, 0x75, 0x67, 0x46
+        ]);
+
+        assert_eq!(TAG_16, [
+            0x07, 0x0a, 0x16, 0xb4, 0x6b, 0x4d, 0x41, 0x44,
+            0xf7, 0x9b, 0xdd, 0x9d, 0xd0, 0x4a, 0x28, 0x7c
+        ]);
+
+        assert_eq!(TAG_40, [
+            0xdf, 0xa6, 0x67, 0x47, 0xde, 0x9a, 0xe6, 0x30,
+            0x30, 0xca, 0x32, 0x61, 0x14, 0x97, 0xc8, 0x27
+        ]);
+
+        assert_eq!(TAG_64, [
+            0x51, 0xf0, 0xbe, 0xbf, 0x7e, 0x3b, 0x9d, 0x92,
+            0xfc, 0x49, 0x74, 0x17, 0x79, 0x36, 0x3c, 0xfe
+        ]);
+    }
+
+    #[test]
+    fn test_aes128_cmac_incremental(){
+        for split in [0, 1, 15, 16, 17, 40, 64] {
+            let mut state = CTX.state();
+            state.update(&MESSAGE[..split]);
+            state.update(&[]);
+            state.update(&MESSAGE[split..]);
+
+            assert_eq!(state.finalize(), CTX.mac(&MESSAGE));
+        }
+    }
+
+    #[test]
+    fn test_aes128_cmac_verify(){
+        let tag = CTX.mac(&MESSAGE);
+
+        assert_eq!(CTX.verify(&MESSAGE, &tag), Ok(()));
+        assert_eq!(CTX.verify(&MESSAGE, &tag[..8]), Ok(()));
+        assert_eq!(CTX.verify(&MESSAGE, &tag[..4]), Err(Error::InvalidLength));
+        assert_eq!(CTX.verify(&MESSAGE[1..], &tag), Err(Error::AuthenticationFailed));
+    }
+}
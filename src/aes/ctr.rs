@@ -0,0 +1,414 @@
+use crate::{Aes128CTR, CounterLayout, CtrReader, RoundKeys};
+use crate::utils::encrypt::encrypt_block;
+
+impl Aes128CTR {
+    /// the iv is used as the initial value of a 128 bit big endian counter
+    pub const fn new(key: [u8; 16], iv: [u8; 16]) -> Self {
+        Self::with_round_keys(RoundKeys::<11>::new(key), iv)
+    }
+
+    pub const fn with_round_keys(keys: RoundKeys<11>, iv: [u8; 16]) -> Self {
+        Self {
+            keys,
+            counter: iv,
+            layout: CounterLayout::Counter128,
+        }
+    }
+
+    /// the counter block is made of the 96 bit nonce followed by a 32 bit big endian counter
+    pub const fn with_nonce(key: [u8; 16], nonce: [u8; 12], counter: u32) -> Self {
+        let mut block = [0u8; 16];
+
+        let mut i = 0;
+        while i < 12 {
+            block[i] = nonce[i];
+            i += 1;
+        }
+
+        let counter = counter.to_be_bytes();
+        while i < 16 {
+            block[i] = counter[i - 12];
+            i += 1;
+        }
+
+        Self {
+            keys: RoundKeys::<11>::new(key),
+            counter: block,
+            layout: CounterLayout::Nonce96Counter32,
+        }
+    }
+
+    pub const fn encrypt<const LENGTH: usize>(
+        &self,
+        plain: &[u8; LENGTH],
+    ) -> [u8; LENGTH] {
+        let mut cipher = *plain;
+        self.apply_keystream(&mut cipher);
+
+        cipher
+    }
+
+    pub const fn decrypt<const LENGTH: usize>(
+        &self,
+        cipher: &[u8; LENGTH],
+    ) -> [u8; LENGTH] {
+        let mut plain = *cipher;
+        self.apply_keystream(&mut plain);
+
+        plain
+    }
+
+    /// xor the keystream into data, encryption and decryption are the same operation
+    pub const fn apply_keystream(&self, data: &mut [u8]) {
+        apply_keystream(&self.keys.enc, &self.counter, Increment::from_layout(self.layout), data)
+    }
+
+    /// xor the keystream into data as if it started at byte `offset` of the stream
+    pub const fn apply_keystream_at(&self, offset: u64, data: &mut [u8]) {
+        apply_keystream_at(&self.keys.enc, &self.counter, Increment::from_layout(self.layout), offset, data)
+    }
+}
+
+impl<'a> CtrReader<'a> {
+    pub const fn new(ctx: Aes128CTR, cipher: &'a [u8]) -> Self {
+        Self {
+            ctx,
+            cipher,
+            #[cfg(feature = "std")]
+            position: 0,
+        }
+    }
+
+    /// length of the cipher, which is also the length of the plain text
+    pub const fn len(&self) -> usize {
+        self.cipher.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.cipher.is_empty()
+    }
+
+    ///
+    /// decrypt the plain text starting at `offset` into buf, returns the number of bytes read.
+    ///
+    /// Only the blocks overlapping the requested range are decrypted. The reader is not modified
+    /// so it can be shared between threads reading different parts of the cipher.
+    ///
+    pub const fn read_at(&self, offset: u64, buf: &mut [u8]) -> usize {
+        if offset >= self.cipher.len() as u64 {
+            return 0;
+        }
+
+        let (_, cipher) = self.cipher.split_at(offset as usize);
+        let length = if buf.len() < cipher.len() { buf.len() } else { cipher.len() };
+
+        let (buf, _) = buf.split_at_mut(length);
+        let (cipher, _) = cipher.split_at(length);
+        buf.copy_from_slice(cipher);
+
+        self.ctx.apply_keystream_at(offset, buf);
+
+        length
+    }
+}
+
+///
+/// how the helpers increment the counter block.
+///
+/// Mirrors `CounterLayout` and adds the little endian counter of GCM-SIV, which is an internal
+/// detail of that mode rather than a layout offered by `Aes128CTR`.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Increment {
+    Counter128,
+    Nonce96Counter32,
+    /// 32 bit little endian counter followed by 96 bits which are never modified
+    LittleEndianCounter32,
+}
+
+impl Increment {
+    const fn from_layout(layout: CounterLayout) -> Self {
+        match layout {
+            CounterLayout::Counter128 => Self::Counter128,
+            CounterLayout::Nonce96Counter32 => Self::Nonce96Counter32,
+        }
+    }
+}
+
+/// increment the counter block according to its layout, wrapping on overflow
+pub(crate) const fn increment_counter(counter: &mut [u8; 16], layout: Increment) {
+    let first = match layout {
+        Increment::Counter128 => 0,
+        Increment::Nonce96Counter32 => 12,
+        Increment::LittleEndianCounter32 => {
+            let value = u32::from_le_bytes([counter[0], counter[1], counter[2], counter[3]]);
+            let value = value.wrapping_add(1).to_le_bytes();
+
+            let mut i = 0;
+            while i < 4 {
+                counter[i] = value[i];
+                i += 1;
+            }
+
+            return;
+        }
+    };
+
+    let mut i = 16;
+
+    while i > first {
+        i -= 1;
+        counter[i] = counter[i].wrapping_add(1);
+
+        //
+        // stop unless that byte overflowed
+        //
+        if counter[i] != 0 {
+            break;
+        }
+    }
+}
+
+/// counter block of the block `blocks` positions after the given one, wrapping on overflow
+pub(crate) const fn add_counter(counter: &[u8; 16], layout: Increment, blocks: u128) -> [u8; 16] {
+    let mut counter = *counter;
+
+    match layout {
+        Increment::Counter128 => {
+            counter = u128::from_be_bytes(counter).wrapping_add(blocks).to_be_bytes();
+        }
+        Increment::Nonce96Counter32 => {
+            let value = u32::from_be_bytes([counter[12], counter[13], counter[14], counter[15]]);
+            let value = value.wrapping_add(blocks as u32).to_be_bytes();
+
+            let mut i = 0;
+            while i < 4 {
+                counter[i + 12] = value[i];
+                i += 1;
+            }
+        }
+        Increment::LittleEndianCounter32 => {
+            let value = u32::from_le_bytes([counter[0], counter[1], counter[2], counter[3]]);
+            let value = value.wrapping_add(blocks as u32).to_le_bytes();
+
+            let mut i = 0;
+            while i < 4 {
+                counter[i] = value[i];
+                i += 1;
+            }
+        }
+    }
+
+    counter
+}
+
+pub(crate) const fn apply_keystream<const ROUND_KEYS: usize>(
+    keys: &[[u8; 16]; ROUND_KEYS],
+    counter: &[u8; 16],
+    layout: Increment,
+    data: &mut [u8],
+) {
+    apply_keystream_at(keys, counter, layout, 0, data)
+}
+
+/// xor the keystream starting at byte `offset` of the stream into data
+pub(crate) const fn apply_keystream_at<const ROUND_KEYS: usize>(
+    keys: &[[u8; 16]; ROUND_KEYS],
+    counter: &[u8; 16],
+    layout: Increment,
+    offset: u64,
+    data: &mut [u8],
+) {
+    let mut counter = add_counter(counter, layout, (offset / 16) as u128);
+    let mut keystream = [0u8; 16];
+
+    let skip = (offset % 16) as usize;
+    let mut i = 0;
+
+    while i < data.len() {
+
+        //
+        // generate a new keystream block at each block boundary
+        //
+        if i == 0 || (i + skip).is_multiple_of(16) {
+            keystream = encrypt_block(&counter, keys);
+            increment_counter(&mut counter, layout);
+        }
+
+        data[i] ^= keystream[(i + skip) % 16];
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128CTR, CtrReader};
+    use super::{add_counter, increment_counter, Increment};
+    use crate::utils::encrypt::encrypt_block;
+    use crate::utils::key_schedule;
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_ctr(){
+        const KEY: [u8; 16] = [
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+            0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c
+        ];
+
+        const IV: [u8; 16] = [
+            0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7,
+            0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff
+        ];
+
+        const PLAIN: [u8; 64] = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96,
+            0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+            0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c,
+            0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+            0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11,
+            0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+            0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17,
+            0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10
+        ];
+
+        const EXPECTED: [u8; 64] = [
+            0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26,
+            0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce,
+            0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff,
+            0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff,
+            0x5a, 0xe4, 0xdf, 0x3e, 0xdb, 0xd5, 0xd3, 0x5e,
+            0x5b, 0x4f, 0x09, 0x02, 0x0d, 0xb0, 0x3e, 0xab,
+            0x1e, 0x03, 0x1d, 0xda, 0x2f, 0xbe, 0x03, 0xd1,
+            0x79, 0x21, 0x70, 0xa0, 0xf3, 0x00, 0x9c, 0xee
+        ];
+
+        const CTX: Aes128CTR = Aes128CTR::new(KEY, IV);
+        const CIPHER: [u8; 64] = CTX.encrypt(&PLAIN);
+
+        assert_eq!(CIPHER, EXPECTED);
+        assert_eq!(CTX.decrypt(&CIPHER), PLAIN);
+    }
+
+    #[test]
+    fn test_encrypt_aes128_ctr_nonce_partial_block(){
+        const KEY: [u8; 16] = [
+            0x76, 0x91, 0xbe, 0x03, 0x5e, 0x50, 0x20, 0xa8,
+            0xac, 0x6e, 0x61, 0x85, 0x29, 0xf9, 0xa0, 0xdc
+        ];
+
+        const NONCE: [u8; 12] = [
+            0x00, 0xe0, 0x01, 0x7b,
+            0x27, 0x77, 0x7f, 0x3f, 0x4a, 0x17, 0x86, 0xf0
+        ];
+
+        const PLAIN: [u8; 36] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+            0x20, 0x21, 0x22, 0x23
+        ];
+
+        const EXPECTED: [u8; 36] = [
+            0xc1, 0xcf, 0x48, 0xa8, 0x9f, 0x2f, 0xfd, 0xd9,
+            0xcf, 0x46, 0x52, 0xe9, 0xef, 0xdb, 0x72, 0xd7,
+            0x45, 0x40, 0xa4, 0x2b, 0xde, 0x6d, 0x78, 0x36,
+            0xd5, 0x9a, 0x5c, 0xea, 0xae, 0xf3, 0x10, 0x53,
+            0x25, 0xb2, 0x07, 0x2f
+        ];
+
+        const CTX: Aes128CTR = Aes128CTR::with_nonce(KEY, NONCE, 1);
+        const CIPHER: [u8; 36] = CTX.encrypt(&PLAIN);
+
+        assert_eq!(CIPHER, EXPECTED);
+
+        let mut plain = CIPHER;
+        CTX.apply_keystream(&mut plain);
+
+        assert_eq!(plain, PLAIN);
+    }
+
+    #[test]
+    fn test_aes128_ctr_counter32_wraps(){
+        const KEY: [u8; 16] = [0x42; 16];
+        const NONCE: [u8; 12] = [0xff; 12];
+
+        const CTX: Aes128CTR = Aes128CTR::with_nonce(KEY, NONCE, u32::MAX);
+        const KEYSTREAM: [u8; 32] = CTX.encrypt(&[0u8; 32]);
+
+        //
+        // the counter wraps to 0 without carrying into the nonce
+        //
+        let mut block = [0xffu8; 16];
+        block[12..].copy_from_slice(&[0, 0, 0, 0]);
+
+        assert_eq!(KEYSTREAM[16..], encrypt_block(&block, &key_schedule(&KEY)));
+    }
+
+    #[test]
+    fn test_increment_little_endian_counter32(){
+        let mut counter = [0xffu8; 16];
+        counter[0] = 0xfe;
+
+        increment_counter(&mut counter, Increment::LittleEndianCounter32);
+        assert_eq!(counter, [0xff; 16]);
+
+        //
+        // the counter wraps to 0 without carrying into the other bytes
+        //
+        increment_counter(&mut counter, Increment::LittleEndianCounter32);
+        assert_eq!(counter[..4], [0, 0, 0, 0]);
+        assert_eq!(counter[4..], [0xff; 12]);
+    }
+
+    #[test]
+    fn test_aes128_ctr_read_at(){
+        const CTX: Aes128CTR = Aes128CTR::new([0x2b; 16], [0xff; 16]);
+        const PLAIN: [u8; 100] = {
+            let mut plain = [0u8; 100];
+
+            let mut i = 0;
+            while i < 100 {
+                plain[i] = i as u8;
+                i += 1;
+            }
+
+            plain
+        };
+
+        const CIPHER: [u8; 100] = CTX.encrypt(&PLAIN);
+
+        //
+        // the counter of the first block is all ones, so it wraps at the second block
+        //
+        let reader = CtrReader::new(Aes128CTR::new([0x2b; 16], [0xff; 16]), &CIPHER);
+
+        for offset in [0, 1, 15, 16, 17, 50, 99] {
+            for length in [0, 1, 16, 33] {
+                let mut buf = [0u8; 33];
+                let read = reader.read_at(offset as u64, &mut buf[..length]);
+
+                let end = (offset + length).min(100);
+                assert_eq!(read, end - offset);
+                assert_eq!(buf[..read], PLAIN[offset..end]);
+            }
+        }
+
+        assert_eq!(reader.read_at(100, &mut [0u8; 16]), 0);
+        assert_eq!(reader.read_at(u64::MAX, &mut [0u8; 16]), 0);
+    }
+
+    #[test]
+    fn test_add_counter(){
+        let mut counter = [0xffu8; 16];
+        counter[15] = 0xfe;
+
+        for layout in [Increment::Counter128, Increment::Nonce96Counter32, Increment::LittleEndianCounter32] {
+            let mut expected = counter;
+
+            for blocks in 0..40 {
+                assert_eq!(add_counter(&counter, layout, blocks), expected);
+                increment_counter(&mut expected, layout);
+            }
+        }
+    }
+}
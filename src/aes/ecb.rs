@@ -1,152 +1,275 @@
-use crate::Aes128ECB;
-use crate::utils::decrypt::{decrypt_block, remove_pkcs7_padding};
-use crate::utils::encrypt::{block_length_with_pkcs7_padding, encrypt_block, pkcs7_byte};
-use crate::utils::key_schedule;
-
-impl Aes128ECB {
-    pub const fn new(key: [u8; 16]) -> Self {
-        Self {
-            key,
-        }
-    }
+use crate::{Aes128ECB, Aes192ECB, Aes256ECB, EcbDecryptor, EcbEncryptor, Error, PlainBuf, RoundKeys};
+use crate::padding::{Padding, PaddingScheme};
+use crate::utils::decrypt::equivalent_decrypt_block;
+use crate::utils::encrypt::encrypt_block;
+use core::marker::PhantomData;
 
-    pub const fn encrypt<const PLAIN_LENGTH: usize, const CIPHER_LENGTH: usize>(
-        &self,
-        plain: &[u8; PLAIN_LENGTH],
-    ) -> [u8; CIPHER_LENGTH] {
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
 
-        //
-        // check block size
-        //
-        if CIPHER_LENGTH != block_length_with_pkcs7_padding(PLAIN_LENGTH) {
-            panic!("Incorrect cipher length");
+/// implement the ECB contexts, which only differ by their key size
+macro_rules! impl_ecb {
+    ($name:ident, $round_keys:literal, $key_length:literal) => {
+        impl $name {
+            pub const fn new(key: [u8; $key_length]) -> Self {
+                Self::with_round_keys(RoundKeys::<$round_keys>::new(key))
+            }
         }
 
-        //
-        // init
-        //
-        let mut cipher = [0u8; CIPHER_LENGTH];
-        let keys = key_schedule(&self.key);
+        impl<P: Padding> $name<P> {
+            pub const fn with_padding(key: [u8; $key_length], _padding: P) -> Self {
+                Self::with_round_keys(RoundKeys::<$round_keys>::new(key))
+            }
 
-        //
-        // copy plain into cipher
-        //
-        let mut i = 0;
-        while i < PLAIN_LENGTH {
-            cipher[i] = plain[i];
-            i += 1;
-        }
+            pub const fn with_round_keys(keys: RoundKeys<$round_keys>) -> Self {
+                Self {
+                    keys,
+                    padding: PhantomData,
+                }
+            }
 
-        //
-        // add pkcs7 bytes
-        //
-        let pkcs7 = pkcs7_byte(PLAIN_LENGTH);
-        while i < CIPHER_LENGTH {
-            cipher[i] = pkcs7;
+            /// start encrypting data fed in several parts
+            pub const fn encryptor(&self) -> EcbEncryptor<$round_keys, P> {
+                EcbEncryptor::new(self.keys)
+            }
 
-            i += 1;
-        }
+            /// start decrypting data fed in several parts
+            pub const fn decryptor(&self) -> EcbDecryptor<$round_keys, P> {
+                EcbDecryptor::new(self.keys)
+            }
 
-        //
-        // convert to 16 bytes blocks and encrypt the blocks
-        //
-        i = 0;
-        let mut block = [0u8; 16];
-        let mut j = 0;
-
-        while i < CIPHER_LENGTH {
-
-            block[j] = cipher[i];
-
-            if i % 16 == 15 {
-                //
-                // encrypt that block
-                //
-                let block_enc = encrypt_block(&block, &keys);
-
-                //
-                // copy the bytes of that block
-                //
-                let mut k = 0;
-                while k < 16 {
-                    cipher[i - 15 + k] = block_enc[k];
-                    k += 1;
+            pub const fn encrypt<const PLAIN_LENGTH: usize, const CIPHER_LENGTH: usize>(
+                &self,
+                plain: &[u8; PLAIN_LENGTH],
+            ) -> [u8; CIPHER_LENGTH] {
+                encrypt(&self.keys, P::SCHEME, plain)
+            }
+
+            /// decrypt and remove the padding, panics if the cipher is invalid
+            #[cfg(feature = "alloc")]
+            pub fn decrypt<const CIPHER_LENGTH: usize>(
+                &self,
+                cipher: &[u8; CIPHER_LENGTH]
+            ) -> Vec<u8> {
+                match self.try_decrypt(cipher) {
+                    Ok(plain) => plain,
+                    Err(err) => panic!("{}", err),
                 }
+            }
 
-                j = 0;
-            } else {
-                j += 1;
+            #[cfg(feature = "alloc")]
+            pub fn try_decrypt<const CIPHER_LENGTH: usize>(
+                &self,
+                cipher: &[u8; CIPHER_LENGTH]
+            ) -> Result<Vec<u8>, Error> {
+                self.decrypt_to_array(cipher).map(|plain| plain.as_slice().to_vec())
             }
 
-            i += 1;
-        }
+            /// decrypt without allocating, usable at compile time
+            pub const fn decrypt_to_array<const CIPHER_LENGTH: usize>(
+                &self,
+                cipher: &[u8; CIPHER_LENGTH]
+            ) -> Result<PlainBuf<CIPHER_LENGTH>, Error> {
+                decrypt_to_array(&self.keys, P::SCHEME, cipher)
+            }
 
-        cipher
-    }
+            /// encrypt data whose length is only known at runtime, panics if unaligned data is not padded
+            #[cfg(feature = "alloc")]
+            pub fn encrypt_vec(&self, plain: &[u8]) -> Vec<u8> {
+                let mut buf = vec![0u8; P::SCHEME.padded_length(plain.len())];
+                buf[..plain.len()].copy_from_slice(plain);
 
-    pub fn decrypt<const CIPHER_LENGTH: usize>(
-        &self,
-        cipher: &[u8; CIPHER_LENGTH]
-    ) -> Vec<u8> {
+                match encrypt_in_place(&self.keys, P::SCHEME, &mut buf, plain.len()) {
+                    Ok(_) => buf,
+                    Err(err) => panic!("{}", err),
+                }
+            }
 
-        //
-        // check cipher length
-        //
-        if CIPHER_LENGTH % 16 != 0 {
-            panic!("Incorrect cipher length");
-        }
+            #[cfg(feature = "alloc")]
+            pub fn decrypt_slice(&self, cipher: &[u8]) -> Result<Vec<u8>, Error> {
+                let mut buf = cipher.to_vec();
+                let length = decrypt_in_place(&self.keys, P::SCHEME, &mut buf)?;
 
-        //
-        // init
-        //
-        let mut plain = [0u8; CIPHER_LENGTH];
-        let keys = key_schedule(&self.key);
+                buf.truncate(length);
+                Ok(buf)
+            }
 
-        //
-        // convert to 16 bytes blocks and encrypt the blocks
-        //
-        let mut i = 0;
-        let mut block = [0u8; 16];
-        let mut j = 0;
-
-        while i < CIPHER_LENGTH {
-            block[j] = cipher[i];
-
-            if i % 16 == 15 {
-                //
-                // encrypt that block
-                //
-                let block_enc = decrypt_block(&block, &keys);
-
-                //
-                // copy the bytes of that block
-                //
-                let mut k = 0;
-                while k < 16 {
-                    plain[i - 15 + k] = block_enc[k];
-                    k += 1;
+            ///
+            /// pad and encrypt the first `msg_len` bytes of buf, returns the cipher.
+            ///
+            /// buf must have room for the padding, see `PaddingScheme::padded_length`.
+            ///
+            pub const fn encrypt_in_place<'a>(&self, buf: &'a mut [u8], msg_len: usize) -> Result<&'a [u8], Error> {
+                match encrypt_in_place(&self.keys, P::SCHEME, buf, msg_len) {
+                    Ok(length) => {
+                        let buf: &[u8] = buf;
+                        Ok(buf.split_at(length).0)
+                    }
+                    Err(err) => Err(err),
                 }
-
-                j = 0;
-            } else {
-                j += 1;
             }
 
-            i += 1;
+            /// decrypt buf and remove the padding, returns the plain data
+            pub const fn decrypt_in_place<'a>(&self, buf: &'a mut [u8]) -> Result<&'a [u8], Error> {
+                match decrypt_in_place(&self.keys, P::SCHEME, buf) {
+                    Ok(length) => {
+                        let buf: &[u8] = buf;
+                        Ok(buf.split_at(length).0)
+                    }
+                    Err(err) => Err(err),
+                }
+            }
         }
+    };
+}
 
-        //
-        // remove the padding
-        //
-        remove_pkcs7_padding(&plain)
+impl_ecb!(Aes128ECB, 11, 16);
+impl_ecb!(Aes192ECB, 13, 24);
+impl_ecb!(Aes256ECB, 15, 32);
+
+pub(crate) const fn encrypt<const ROUND_KEYS: usize, const PLAIN_LENGTH: usize, const CIPHER_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    plain: &[u8; PLAIN_LENGTH],
+) -> [u8; CIPHER_LENGTH] {
+
+    //
+    // check block size
+    //
+    if CIPHER_LENGTH != padding.padded_length(PLAIN_LENGTH) || !CIPHER_LENGTH.is_multiple_of(16) {
+        panic!("Incorrect cipher length");
+    }
+
+    //
+    // copy plain into cipher
+    //
+    let mut cipher = [0u8; CIPHER_LENGTH];
+
+    let mut i = 0;
+    while i < PLAIN_LENGTH {
+        cipher[i] = plain[i];
+        i += 1;
+    }
+
+    match encrypt_in_place(keys, padding, &mut cipher, PLAIN_LENGTH) {
+        Ok(_) => cipher,
+        Err(_) => panic!("Incorrect cipher length"),
+    }
+}
+
+pub(crate) const fn decrypt_to_array<const ROUND_KEYS: usize, const CIPHER_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    cipher: &[u8; CIPHER_LENGTH]
+) -> Result<PlainBuf<CIPHER_LENGTH>, Error> {
+    let mut plain = *cipher;
+
+    match decrypt_in_place(keys, padding, &mut plain) {
+        Ok(length) => Ok(PlainBuf::new(plain, length)),
+        Err(err) => Err(err),
+    }
+}
+
+/// pad the first `length` bytes of buf and encrypt them, returns the cipher length
+pub(crate) const fn encrypt_in_place<const ROUND_KEYS: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    buf: &mut [u8],
+    length: usize,
+) -> Result<usize, Error> {
+
+    //
+    // check the buffer has room for the padding, without padding the data must be block aligned
+    //
+    let cipher_length = padding.padded_length(length);
+
+    if cipher_length > buf.len() || !cipher_length.is_multiple_of(16) {
+        return Err(Error::InvalidLength);
+    }
+
+    let (cipher, _) = buf.split_at_mut(cipher_length);
+
+    //
+    // add the padding bytes
+    //
+    padding.pad(cipher, length);
+
+    encrypt_blocks(keys, cipher);
+
+    Ok(cipher_length)
+}
+
+/// decrypt buf and check the padding, returns the plain length
+pub(crate) const fn decrypt_in_place<const ROUND_KEYS: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    padding: PaddingScheme,
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+
+    //
+    // check cipher length
+    //
+    if !buf.len().is_multiple_of(16) {
+        return Err(Error::InvalidLength);
+    }
+
+    decrypt_blocks(keys, buf);
+
+    //
+    // remove the padding
+    //
+    padding.unpadded_length(buf)
+}
+
+/// encrypt block aligned data in place
+pub(crate) const fn encrypt_blocks<const ROUND_KEYS: usize>(keys: &RoundKeys<ROUND_KEYS>, data: &mut [u8]) {
+    let mut i = 0;
+    while i < data.len() {
+        let block_enc = encrypt_block(&read_block(data, i), &keys.enc);
+        write_block(data, i, &block_enc);
+
+        i += 16;
+    }
+}
+
+/// decrypt block aligned data in place
+pub(crate) const fn decrypt_blocks<const ROUND_KEYS: usize>(keys: &RoundKeys<ROUND_KEYS>, data: &mut [u8]) {
+    let mut i = 0;
+    while i < data.len() {
+        let block_dec = equivalent_decrypt_block(&read_block(data, i), &keys.dec);
+        write_block(data, i, &block_dec);
+
+        i += 16;
+    }
+}
+
+pub(crate) const fn read_block(data: &[u8], start: usize) -> [u8; 16] {
+    let mut block = [0u8; 16];
+
+    let mut i = 0;
+    while i < 16 {
+        block[i] = data[start + i];
+        i += 1;
+    }
+
+    block
+}
+
+pub(crate) const fn write_block(data: &mut [u8], start: usize, block: &[u8; 16]) {
+    let mut i = 0;
+    while i < 16 {
+        data[start + i] = block[i];
+        i += 1;
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Aes128ECB;
+    use crate::{Aes128ECB, Aes192ECB, Aes256ECB, Error, PlainBuf};
+    use crate::padding::NoPadding;
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_encrypt_decrypt_aes128_ecb(){
         const KEY: [u8; 16] = [
             0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
@@ -167,4 +290,201 @@ mod tests {
 
         assert_eq!(PLAIN, plain.as_slice());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_encrypt_decrypt_aes192_ecb(){
+        const KEY: [u8; 24] = [
+            0x8e, 0x73, 0xb0, 0xf7, 0xda, 0x0e, 0x64, 0x52,
+            0xc8, 0x10, 0xf3, 0x2b, 0x80, 0x90, 0x79, 0xe5,
+            0x62, 0xf8, 0xea, 0xd2, 0x52, 0x2c, 0x6b, 0x7b
+        ];
+
+        const PLAIN: [u8; 16] = [
+            0x6b, 0xc1, 0xbe, 0xe2,
+            0x2e, 0x40, 0x9f, 0x96,
+            0xe9, 0x3d, 0x7e, 0x11,
+            0x73, 0x93, 0x17, 0x2a
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0xbd, 0x33, 0x4f, 0x1d,
+            0x6e, 0x45, 0xf2, 0x5f,
+            0xf7, 0x12, 0xa2, 0x14,
+            0x57, 0x1f, 0xa5, 0xcc
+        ];
+
+        const CTX: Aes192ECB = Aes192ECB::new(KEY);
+        const CIPHER: [u8; 32] = CTX.encrypt::<16, 32>(&PLAIN);
+
+        assert_eq!(CIPHER[..16], EXPECTED);
+
+        let plain = CTX.decrypt_to_array(&CIPHER).unwrap();
+
+        assert_eq!(PLAIN, plain.as_slice());
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes256_ecb(){
+        const KEY: [u8; 32] = [
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe,
+            0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81,
+            0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7,
+            0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4
+        ];
+
+        const PLAIN: [u8; 16] = [
+            0x6b, 0xc1, 0xbe, 0xe2,
+            0x2e, 0x40, 0x9f, 0x96,
+            0xe9, 0x3d, 0x7e, 0x11,
+            0x73, 0x93, 0x17, 0x2a
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0xf3, 0xee, 0xd1, 0xbd,
+            0xb5, 0xd2, 0xa0, 0x3c,
+            0x06, 0x4b, 0x5a, 0x7e,
+            0x3d, 0xb1, 0x81, 0xf8
+        ];
+
+        const CTX: Aes256ECB = Aes256ECB::new(KEY);
+        const CIPHER: [u8; 32] = CTX.encrypt::<16, 32>(&PLAIN);
+
+        assert_eq!(CIPHER[..16], EXPECTED);
+
+        let plain = CTX.decrypt_to_array(&CIPHER).unwrap();
+
+        assert_eq!(PLAIN, plain.as_slice());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_try_decrypt_aes128_ecb_errors(){
+        const CTX: Aes128ECB = Aes128ECB::new([0x42; 16]);
+
+        assert_eq!(CTX.try_decrypt(&[0u8; 15]), Err(Error::InvalidLength));
+        assert_eq!(CTX.try_decrypt(&[0u8; 0]), Err(Error::InvalidLength));
+
+        //
+        // last byte decrypts to a padding of 0x00 then 0x11
+        //
+        const ZERO: [u8; 32] = CTX.encrypt::<16, 32>(&[0u8; 16]);
+        assert_eq!(CTX.try_decrypt::<16>(ZERO[..16].try_into().unwrap()), Err(Error::InvalidPadding));
+
+        const LARGE: [u8; 32] = CTX.encrypt::<16, 32>(&[0x11; 16]);
+        assert_eq!(CTX.try_decrypt::<16>(LARGE[..16].try_into().unwrap()), Err(Error::InvalidPadding));
+
+        const INCONSISTENT: [u8; 32] = CTX.encrypt::<16, 32>(&[
+            0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0x41,
+            0x41, 0x41, 0x41, 0x41, 0x41, 0x01, 0x03, 0x03
+        ]);
+        assert_eq!(CTX.try_decrypt::<16>(INCONSISTENT[..16].try_into().unwrap()), Err(Error::InvalidPadding));
+    }
+
+    #[test]
+    fn test_decrypt_to_array_aes128_ecb(){
+        const KEY: [u8; 16] = [0x42; 16];
+        const PLAIN: [u8; 21] = *b"compile time decrypt!";
+
+        const CTX: Aes128ECB = Aes128ECB::new(KEY);
+        const CIPHER: [u8; 32] = CTX.encrypt(&PLAIN);
+
+        //
+        // round trip checked by the compiler
+        //
+        const DECRYPTED: PlainBuf<32> = match CTX.decrypt_to_array(&CIPHER) {
+            Ok(plain) => plain,
+            Err(_) => panic!("decryption failed"),
+        };
+
+        const _: () = assert!(DECRYPTED.len() == PLAIN.len());
+
+        assert_eq!(DECRYPTED.as_slice(), PLAIN);
+        assert!(matches!(CTX.decrypt_to_array(&[0u8; 8]), Err(Error::InvalidLength)));
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_ecb_no_padding(){
+        const KEY: [u8; 16] = [
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+            0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c
+        ];
+
+        const PLAIN: [u8; 64] = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96,
+            0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+            0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c,
+            0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+            0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11,
+            0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+            0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17,
+            0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10
+        ];
+
+        const EXPECTED: [u8; 64] = [
+            0x3a, 0xd7, 0x7b, 0xb4, 0x0d, 0x7a, 0x36, 0x60,
+            0xa8, 0x9e, 0xca, 0xf3, 0x24, 0x66, 0xef, 0x97,
+            0xf5, 0xd3, 0xd5, 0x85, 0x03, 0xb9, 0x69, 0x9d,
+            0xe7, 0x85, 0x89, 0x5a, 0x96, 0xfd, 0xba, 0xaf,
+            0x43, 0xb1, 0xcd, 0x7f, 0x59, 0x8e, 0xce, 0x23,
+            0x88, 0x1b, 0x00, 0xe3, 0xed, 0x03, 0x06, 0x88,
+            0x7b, 0x0c, 0x78, 0x5e, 0x27, 0xe8, 0xad, 0x3f,
+            0x82, 0x23, 0x20, 0x71, 0x04, 0x72, 0x5d, 0xd4
+        ];
+
+        const CTX: Aes128ECB<NoPadding> = Aes128ECB::with_padding(KEY, NoPadding);
+        const CIPHER: [u8; 64] = CTX.encrypt(&PLAIN);
+
+        assert_eq!(CIPHER, EXPECTED);
+        assert_eq!(CTX.decrypt_to_array(&CIPHER).unwrap().as_slice(), PLAIN);
+    }
+
+    #[test]
+    fn test_unaligned_aes128_ecb_no_padding(){
+        const CTX: Aes128ECB<NoPadding> = Aes128ECB::with_padding([0x2b; 16], NoPadding);
+
+        let mut buf = [0u8; 32];
+        assert_eq!(CTX.encrypt_in_place(&mut buf, 5), Err(Error::InvalidLength));
+        assert_eq!(CTX.encrypt_in_place(&mut buf, 17), Err(Error::InvalidLength));
+        assert_eq!(CTX.encrypt_in_place(&mut buf, 32).map(<[u8]>::len), Ok(32));
+    }
+
+    #[test]
+    #[should_panic(expected = "Incorrect cipher length")]
+    fn test_unaligned_aes128_ecb_no_padding_const(){
+        Aes128ECB::with_padding([0x2b; 16], NoPadding).encrypt::<5, 16>(b"hello");
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_in_place_aes128_ecb(){
+        const CTX: Aes128ECB = Aes128ECB::new([0x2b; 16]);
+        const CIPHER: [u8; 32] = CTX.encrypt::<20, 32>(&[7u8; 20]);
+
+        let mut buf = [0u8; 40];
+        buf[..20].copy_from_slice(&[7u8; 20]);
+
+        assert_eq!(CTX.encrypt_in_place(&mut buf, 20), Ok(&CIPHER[..]));
+        assert_eq!(CTX.decrypt_in_place(&mut buf[..32]), Ok(&[7u8; 20][..]));
+
+        //
+        // the buffer must have room for the padding and the cipher must be block aligned
+        //
+        assert_eq!(CTX.encrypt_in_place(&mut buf[..31], 20), Err(Error::InvalidLength));
+        assert_eq!(CTX.decrypt_in_place(&mut buf[..31]), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_encrypt_vec_decrypt_slice_aes256_ecb(){
+        let ctx = Aes256ECB::new([0x60; 32]);
+
+        for length in [0, 1, 15, 16, 17, 100] {
+            let plain: alloc::vec::Vec<u8> = (0..length as u8).collect();
+            let cipher = ctx.encrypt_vec(&plain);
+
+            assert_eq!(cipher.len(), (length / 16 + 1) * 16);
+            assert_eq!(ctx.decrypt_slice(&cipher), Ok(plain));
+        }
+
+        assert_eq!(ctx.decrypt_slice(&[0u8; 17]), Err(Error::InvalidLength));
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{Aes128GCM, Error, RoundKeys};
+use crate::aes::ctr::{apply_keystream, increment_counter, Increment};
+use crate::utils::constant_time_eq;
+use crate::utils::encrypt::encrypt_block;
+
+/// GCM reduction polynomial x^128 + x^7 + x^2 + x + 1 in the bit reflected order of the spec
+pub(crate) const R: u128 = 0xE1 << 120;
+
+impl Aes128GCM {
+    pub const fn new(key: [u8; 16]) -> Self {
+        Self::with_round_keys(RoundKeys::<11>::new(key))
+    }
+
+    /// the hash subkey is derived once from the round keys
+    pub const fn with_round_keys(keys: RoundKeys<11>) -> Self {
+        let h = u128::from_be_bytes(encrypt_block(&[0u8; 16], &keys.enc));
+
+        Self {
+            keys,
+            h,
+        }
+    }
+
+    /// encrypt and authenticate, returns the cipher and the full 128 bit tag
+    ///
+    /// The tag may be truncated by the caller as long as it keeps a length accepted by `decrypt`.
+    pub const fn encrypt<const LENGTH: usize>(
+        &self,
+        iv: &[u8],
+        aad: &[u8],
+        plain: &[u8; LENGTH],
+    ) -> ([u8; LENGTH], [u8; 16]) {
+
+        //
+        // check iv length, SP 800-38D requires at least 1 bit
+        //
+        if iv.is_empty() {
+            panic!("Invalid nonce length");
+        }
+
+        let keys = &self.keys.enc;
+        let j0 = pre_counter_block(self.h, iv);
+
+        //
+        // encrypt starting from inc32(J0)
+        //
+        let mut counter = j0;
+        increment_counter(&mut counter, Increment::Nonce96Counter32);
+
+        let mut cipher = *plain;
+        apply_keystream(keys, &counter, Increment::Nonce96Counter32, &mut cipher);
+
+        let tag = compute_tag(keys, self.h, &j0, aad, &cipher);
+
+        (cipher, tag)
+    }
+
+    /// verify the tag and decrypt, returns an error if the tag does not match
+    ///
+    /// Tags of 16, 15, 14, 13, 12, 8 and 4 bytes are accepted as allowed by NIST SP 800-38D,
+    /// the iv must not be empty.
+    pub const fn decrypt<const LENGTH: usize>(
+        &self,
+        iv: &[u8],
+        aad: &[u8],
+        cipher: &[u8; LENGTH],
+        tag: &[u8],
+    ) -> Result<[u8; LENGTH], Error> {
+
+        //
+        // check iv and tag lengths
+        //
+        if iv.is_empty() || !matches!(tag.len(), 4 | 8 | 12..=16) {
+            return Err(Error::InvalidLength);
+        }
+
+        let keys = &self.keys.enc;
+        let j0 = pre_counter_block(self.h, iv);
+
+        //
+        // authenticate the cipher before decrypting anything
+        //
+        let expected = compute_tag(keys, self.h, &j0, aad, cipher);
+
+        if !constant_time_eq(&expected, tag) {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        let mut counter = j0;
+        increment_counter(&mut counter, Increment::Nonce96Counter32);
+
+        let mut plain = *cipher;
+        apply_keystream(keys, &counter, Increment::Nonce96Counter32, &mut plain);
+
+        Ok(plain)
+    }
+}
+
+/// multiplication in GF(2^128) as defined by GCM, without any secret dependent branch
+pub(crate) const fn gf128_multiply(x: u128, y: u128) -> u128 {
+    let mut z = 0;
+    let mut v = y;
+
+    let mut i = 0;
+
+    while i < 128 {
+        //
+        // add v when the i-th bit of x (msb first) is set
+        //
+        z ^= v & 0u128.wrapping_sub((x >> (127 - i)) & 1);
+
+        //
+        // multiply v by x, reducing if the lowest bit falls off
+        //
+        v = (v >> 1) ^ (R & 0u128.wrapping_sub(v & 1));
+
+        i += 1;
+    }
+
+    z
+}
+
+/// absorb data into the GHASH state y, the last partial block is padded with zeros
+pub(crate) const fn ghash(h: u128, y: u128, data: &[u8]) -> u128 {
+    let mut y = y;
+    let mut i = 0;
+
+    while i < data.len() {
+        let mut block = [0u8; 16];
+
+        let mut j = 0;
+        while j < 16 && i + j < data.len() {
+            block[j] = data[i + j];
+            j += 1;
+        }
+
+        y = gf128_multiply(y ^ u128::from_be_bytes(block), h);
+        i += 16;
+    }
+
+    y
+}
+
+/// derive J0 from the iv
+const fn pre_counter_block(h: u128, iv: &[u8]) -> [u8; 16] {
+
+    //
+    // 96 bit iv are used as is followed by a 32 bit counter set to 1
+    //
+    if iv.len() == 12 {
+        let mut j0 = [0u8; 16];
+
+        let mut i = 0;
+        while i < 12 {
+            j0[i] = iv[i];
+            i += 1;
+        }
+
+        j0[15] = 1;
+        return j0;
+    }
+
+    //
+    // any other length is hashed along with its bit length
+    //
+    let y = ghash(h, 0, iv);
+    gf128_multiply(y ^ (iv.len() as u128 * 8), h).to_be_bytes()
+}
+
+const fn compute_tag<const ROUND_KEYS: usize>(
+    keys: &[[u8; 16]; ROUND_KEYS],
+    h: u128,
+    j0: &[u8; 16],
+    aad: &[u8],
+    cipher: &[u8],
+) -> [u8; 16] {
+    let mut s = ghash(h, 0, aad);
+    s = ghash(h, s, cipher);
+
+    //
+    // final block holds the bit lengths of the aad and the cipher
+    //
+    let lengths = ((aad.len() as u128 * 8) << 64) | (cipher.len() as u128 * 8);
+    s = gf128_multiply(s ^ lengths, h);
+
+    let mask = encrypt_block(j0, keys);
+    let s = s.to_be_bytes();
+
+    let mut tag = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        tag[i] = mask[i] ^ s[i];
+        i += 1;
+    }
+
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128GCM, Error};
+
+    const KEY: [u8; 16] = [
+        0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c,
+        0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08
+    ];
+
+    const PLAIN: [u8; 60] = [
+        0xd9, 0x31, 0x32, 0x25, 0xf8, 0x84, 0x06, 0xe5,
+        0xa5, 0x59, 0x09, 0xc5, 0xaf, 0xf5, 0x26, 0x9a,
+        0x86, 0xa7, 0xa9, 0x53, 0x15, 0x34, 0xf7, 0xda,
+        0x2e, 0x4c, 0x30, 0x3d, 0x8a, 0x31, 0x8a, 0x72,
+        0x1c, 0x3c, 0x0c, 0x95, 0x95, 0x68, 0x09, 0x53,
+        0x2f, 0xcf, 0x0e, 0x24, 0x49, 0xa6, 0xb5, 0x25,
+        0xb1, 0x6a, 0xed, 0xf5, 0xaa, 0x0d, 0xe6, 0x57,
+        0xba, 0x63, 0x7b, 0x39
+    ];
+
+    const AAD: [u8; 20] = [
+        0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef,
+        0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef,
+        0xab, 0xad, 0xda, 0xd2
+    ];
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_gcm_zero(){
+        const EXPECTED: [u8; 16] = [
+            0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92,
+            0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe, 0x78
+        ];
+
+        const EXPECTED_TAG: [u8; 16] = [
+            0xab, 0x6e, 0x47, 0xd4, 0x2c, 0xec, 0x13, 0xbd,
+            0xf5, 0x3a, 0x67, 0xb2, 0x12, 0x57, 0xbd, 0xdf
+        ];
+
+        const CTX: Aes128GCM = Aes128GCM::new([0u8; 16]);
+        const SEALED: ([u8; 16], [u8; 16]) = CTX.encrypt(&[0u8; 12], &[], &[0u8; 16]);
+
+        assert_eq!(SEALED.0, EXPECTED);
+        assert_eq!(SEALED.1, EXPECTED_TAG);
+
+        assert_eq!(CTX.decrypt(&[0u8; 12], &[], &SEALED.0, &SEALED.1), Ok([0u8; 16]));
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_gcm_aad(){
+        const IV: [u8; 12] = [
+            0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad,
+            0xde, 0xca, 0xf8, 0x88
+        ];
+
+        const EXPECTED: [u8; 60] = [
+            0x42, 0x83, 0x1e, 0xc2, 0x21, 0x77, 0x74, 0x24,
+            0x4b, 0x72, 0x21, 0xb7, 0x84, 0xd0, 0xd4, 0x9c,
+            0xe3, 0xaa, 0x21, 0x2f, 0x2c, 0x02, 0xa4, 0xe0,
+            0x35, 0xc1, 0x7e, 0x23, 0x29, 0xac, 0xa1, 0x2e,
+            0x21, 0xd5, 0x14, 0xb2, 0x54, 0x66, 0x93, 0x1c,
+            0x7d, 0x8f, 0x6a, 0x5a, 0xac, 0x84, 0xaa, 0x05,
+            0x1b, 0xa3, 0x0b, 0x39, 0x6a, 0x0a, 0xac, 0x97,
+            0x3d, 0x58, 0xe0, 0x91
+        ];
+
+        const EXPECTED_TAG: [u8; 16] = [
+            0x5b, 0xc9, 0x4f, 0xbc, 0x32, 0x21, 0xa5, 0xdb,
+            0x94, 0xfa, 0xe9, 0x5a, 0xe7, 0x12, 0x1a, 0x47
+        ];
+
+        const CTX: Aes128GCM = Aes128GCM::new(KEY);
+        const SEALED: ([u8; 60], [u8; 16]) = CTX.encrypt(&IV, &AAD, &PLAIN);
+
+        assert_eq!(SEALED.0, EXPECTED);
+        assert_eq!(SEALED.1, EXPECTED_TAG);
+
+        assert_eq!(CTX.decrypt(&IV, &AAD, &SEALED.0, &SEALED.1), Ok(PLAIN));
+
+        //
+        // truncated tags are accepted
+        //
+        assert_eq!(CTX.decrypt(&IV, &AAD, &SEALED.0, &SEALED.1[..12]), Ok(PLAIN));
+        assert_eq!(CTX.decrypt(&IV, &AAD, &SEALED.0, &SEALED.1[..4]), Ok(PLAIN));
+        assert_eq!(CTX.decrypt(&IV, &AAD, &SEALED.0, &SEALED.1[..10]), Err(Error::InvalidLength));
+
+        //
+        // any modification is rejected
+        //
+        let mut cipher = SEALED.0;
+        cipher[0] ^= 1;
+        assert_eq!(CTX.decrypt(&IV, &AAD, &cipher, &SEALED.1), Err(Error::AuthenticationFailed));
+        assert_eq!(CTX.decrypt(&IV, &AAD[1..], &SEALED.0, &SEALED.1), Err(Error::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_encrypt_aes128_gcm_short_iv(){
+        const IV: [u8; 8] = [
+            0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad
+        ];
+
+        const EXPECTED: [u8; 60] = [
+            0x61, 0x35, 0x3b, 0x4c, 0x28, 0x06, 0x93, 0x4a,
+            0x77, 0x7f, 0xf5, 0x1f, 0xa2, 0x2a, 0x47, 0x55,
+            0x69, 0x9b, 0x2a, 0x71, 0x4f, 0xcd, 0xc6, 0xf8,
+            0x37, 0x66, 0xe5, 0xf9, 0x7b, 0x6c, 0x74, 0x23,
+            0x73, 0x80, 0x69, 0x00, 0xe4, 0x9f, 0x24, 0xb2,
+            0x2b, 0x09, 0x75, 0x44, 0xd4, 0x89, 0x6b, 0x42,
+            0x49, 0x89, 0xb5, 0xe1, 0xeb, 0xac, 0x0f, 0x07,
+            0xc2, 0x3f, 0x45, 0x98
+        ];
+
+        const EXPECTED_TAG: [u8; 16] = [
+            0x36, 0x12, 0xd2, 0xe7, 0x9e, 0x3b, 0x07, 0x85,
+            0x56, 0x1b, 0xe1, 0x4a, 0xac, 0xa2, 0xfc, 0xcb
+        ];
+
+        const CTX: Aes128GCM = Aes128GCM::new(KEY);
+        const SEALED: ([u8; 60], [u8; 16]) = CTX.encrypt(&IV, &AAD, &PLAIN);
+
+        assert_eq!(SEALED.0, EXPECTED);
+        assert_eq!(SEALED.1, EXPECTED_TAG);
+    }
+
+    #[test]
+    fn test_encrypt_aes128_gcm_long_iv(){
+        const IV: [u8; 60] = [
+            0x93, 0x13, 0x22, 0x5d, 0xf8, 0x84, 0x06, 0xe5,
+            0x55, 0x90, 0x9c, 0x5a, 0xff, 0x52, 0x69, 0xaa,
+            0x6a, 0x7a, 0x95, 0x38, 0x53, 0x4f, 0x7d, 0xa1,
+            0xe4, 0xc3, 0x03, 0xd2, 0xa3, 0x18, 0xa7, 0x28,
+            0xc3, 0xc0, 0xc9, 0x51, 0x56, 0x80, 0x95, 0x39,
+            0xfc, 0xf0, 0xe2, 0x42, 0x9a, 0x6b, 0x52, 0x54,
+            0x16, 0xae, 0xdb, 0xf5, 0xa0, 0xde, 0x6a, 0x57,
+            0xa6, 0x37, 0xb3, 0x9b
+        ];
+
+        const EXPECTED: [u8; 60] = [
+            0x8c, 0xe2, 0x49, 0x98, 0x62, 0x56, 0x15, 0xb6,
+            0x03, 0xa0, 0x33, 0xac, 0xa1, 0x3f, 0xb8, 0x94,
+            0xbe, 0x91, 0x12, 0xa5, 0xc3, 0xa2, 0x11, 0xa8,
+            0xba, 0x26, 0x2a, 0x3c, 0xca, 0x7e, 0x2c, 0xa7,
+            0x01, 0xe4, 0xa9, 0xa4, 0xfb, 0xa4, 0x3c, 0x90,
+            0xcc, 0xdc, 0xb2, 0x81, 0xd4, 0x8c, 0x7c, 0x6f,
+            0xd6, 0x28, 0x75, 0xd2, 0xac, 0xa4, 0x17, 0x03,
+            0x4c, 0x34, 0xae, 0xe5
+        ];
+
+        const EXPECTED_TAG: [u8; 16] = [
+            0x61, 0x9c, 0xc5, 0xae, 0xff, 0xfe, 0x0b, 0xfa,
+            0x46, 0x2a, 0xf4, 0x3c, 0x16, 0x99, 0xd0, 0x50
+        ];
+
+        const CTX: Aes128GCM = Aes128GCM::new(KEY);
+        const SEALED: ([u8; 60], [u8; 16]) = CTX.encrypt(&IV, &AAD, &PLAIN);
+
+        assert_eq!(SEALED.0, EXPECTED);
+        assert_eq!(SEALED.1, EXPECTED_TAG);
+
+        assert_eq!(CTX.decrypt(&IV, &AAD, &SEALED.0, &SEALED.1), Ok(PLAIN));
+    }
+
+    #[test]
+    fn test_decrypt_aes128_gcm_empty_iv(){
+        const CTX: Aes128GCM = Aes128GCM::new(KEY);
+
+        assert_eq!(CTX.decrypt(&[], &AAD, &PLAIN, &[0u8; 16]), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid nonce length")]
+    fn test_encrypt_aes128_gcm_empty_iv(){
+        Aes128GCM::new(KEY).encrypt(&[], &AAD, &PLAIN);
+    }
+}
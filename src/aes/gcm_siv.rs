@@ -0,0 +1,273 @@
+use crate::{Aes128GCMSIV, Error, RoundKeys};
+use crate::aes::ctr::{apply_keystream, Increment};
+use crate::aes::gcm::{gf128_multiply, R};
+use crate::utils::constant_time_eq;
+use crate::utils::encrypt::encrypt_block;
+
+impl Aes128GCMSIV {
+    pub const fn new(key: [u8; 16]) -> Self {
+        Self::with_round_keys(RoundKeys::<11>::new(key))
+    }
+
+    pub const fn with_round_keys(keys: RoundKeys<11>) -> Self {
+        Self {
+            keys,
+        }
+    }
+
+    ///
+    /// encrypt and authenticate, returns the cipher and the tag.
+    ///
+    /// Reusing a nonce only reveals whether the same plain text and aad were sealed twice.
+    ///
+    pub const fn encrypt<const LENGTH: usize>(
+        &self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        plain: &[u8; LENGTH],
+    ) -> ([u8; LENGTH], [u8; 16]) {
+        let (auth_key, enc_keys) = derive_keys(&self.keys, nonce);
+        let tag = compute_tag(auth_key, &enc_keys, nonce, aad, plain);
+
+        let mut cipher = *plain;
+        apply_keystream(&enc_keys, &counter(&tag), Increment::LittleEndianCounter32, &mut cipher);
+
+        (cipher, tag)
+    }
+
+    /// decrypt and check the tag, nothing is returned unless the cipher is authentic
+    pub const fn decrypt<const LENGTH: usize>(
+        &self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        cipher: &[u8; LENGTH],
+        tag: &[u8; 16],
+    ) -> Result<[u8; LENGTH], Error> {
+        let (auth_key, enc_keys) = derive_keys(&self.keys, nonce);
+
+        //
+        // the tag is computed over the plain text so decrypt first
+        //
+        let mut plain = *cipher;
+        apply_keystream(&enc_keys, &counter(tag), Increment::LittleEndianCounter32, &mut plain);
+
+        let expected = compute_tag(auth_key, &enc_keys, nonce, aad, &plain);
+
+        if !constant_time_eq(&expected, tag) {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        Ok(plain)
+    }
+}
+
+///
+/// per nonce message authentication key and round keys of the message encryption key.
+///
+/// Each half of a key is the first 8 bytes of the encryption of a little endian counter
+/// followed by the nonce.
+///
+const fn derive_keys(keys: &RoundKeys<11>, nonce: &[u8; 12]) -> (u128, [[u8; 16]; 11]) {
+    let mut derived = [0u8; 32];
+    let mut block = [0u8; 16];
+
+    let mut i = 0;
+    while i < 12 {
+        block[i + 4] = nonce[i];
+        i += 1;
+    }
+
+    let mut counter = 0;
+    while counter < 4 {
+        block[0] = counter as u8;
+        let encrypted = encrypt_block(&block, &keys.enc);
+
+        i = 0;
+        while i < 8 {
+            derived[counter * 8 + i] = encrypted[i];
+            i += 1;
+        }
+
+        counter += 1;
+    }
+
+    let (auth_key, enc_key) = derived.split_at(16);
+
+    (
+        u128::from_le_bytes(*auth_key.first_chunk().unwrap()),
+        RoundKeys::<11>::new(*enc_key.first_chunk().unwrap()).enc,
+    )
+}
+
+/// the counter starts from the tag with its most significant bit set
+const fn counter(tag: &[u8; 16]) -> [u8; 16] {
+    let mut counter = *tag;
+    counter[15] |= 0x80;
+
+    counter
+}
+
+/// POLYVAL over the padded aad, the padded plain text and their bit lengths, masked with the nonce and encrypted
+const fn compute_tag(
+    auth_key: u128,
+    enc_keys: &[[u8; 16]; 11],
+    nonce: &[u8; 12],
+    aad: &[u8],
+    plain: &[u8],
+) -> [u8; 16] {
+    let h = polyval_key(auth_key);
+
+    let mut lengths = [0u8; 16];
+    let aad_bits = (aad.len() as u64 * 8).to_le_bytes();
+    let plain_bits = (plain.len() as u64 * 8).to_le_bytes();
+
+    let mut i = 0;
+    while i < 8 {
+        lengths[i] = aad_bits[i];
+        lengths[i + 8] = plain_bits[i];
+        i += 1;
+    }
+
+    let s = polyval(h, 0, aad);
+    let s = polyval(h, s, plain);
+    let s = polyval(h, s, &lengths);
+
+    let mut block = s.to_le_bytes();
+
+    i = 0;
+    while i < 12 {
+        block[i] ^= nonce[i];
+        i += 1;
+    }
+
+    block[15] &= 0x7f;
+
+    encrypt_block(&block, enc_keys)
+}
+
+///
+/// POLYVAL key in the representation of `gf128_multiply`.
+///
+/// POLYVAL is GHASH on byte reversed blocks with the key multiplied by x, see RFC 8452 appendix A.
+///
+const fn polyval_key(h: u128) -> u128 {
+    (h >> 1) ^ (R & 0u128.wrapping_sub(h & 1))
+}
+
+/// absorb data zero padded to whole blocks, the result is the little endian value of the POLYVAL bytes
+const fn polyval(h: u128, s: u128, data: &[u8]) -> u128 {
+    let mut s = s;
+    let mut i = 0;
+
+    while i < data.len() {
+        let mut block = [0u8; 16];
+
+        let mut j = 0;
+        while j < 16 && i + j < data.len() {
+            block[j] = data[i + j];
+            j += 1;
+        }
+
+        s = gf128_multiply(s ^ u128::from_le_bytes(block), h);
+        i += 16;
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128GCMSIV, Error};
+    use super::{polyval, polyval_key};
+
+    const KEY: [u8; 16] = [
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+
+    const NONCE: [u8; 12] = [0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    const CTX: Aes128GCMSIV = Aes128GCMSIV::new(KEY);
+
+    #[test]
+    fn test_polyval(){
+        //
+        // RFC 8452 appendix A
+        //
+        const H: [u8; 16] = [
+            0x25, 0x62, 0x93, 0x47, 0x58, 0x92, 0x42, 0x76,
+            0x1d, 0x31, 0xf8, 0x26, 0xba, 0x4b, 0x75, 0x7b
+        ];
+
+        const DATA: [u8; 32] = [
+            0x4f, 0x4f, 0x95, 0x66, 0x8c, 0x83, 0xdf, 0xb6,
+            0x40, 0x17, 0x62, 0xbb, 0x2d, 0x01, 0xa2, 0x62,
+            0xd1, 0xa2, 0x4d, 0xdd, 0x27, 0x21, 0xd0, 0x06,
+            0xbb, 0xe4, 0x5f, 0x20, 0xd3, 0xc9, 0xf3, 0x62
+        ];
+
+        let s = polyval(polyval_key(u128::from_le_bytes(H)), 0, &DATA);
+
+        assert_eq!(s.to_le_bytes(), [
+            0xf7, 0xa3, 0xb4, 0x7b, 0x84, 0x61, 0x19, 0xfa,
+            0xe5, 0xb7, 0x86, 0x6c, 0xf5, 0xe5, 0xb7, 0x7e
+        ]);
+    }
+
+    #[test]
+    fn test_aes128_gcm_siv(){
+        //
+        // RFC 8452 appendix C.1
+        //
+        const SEALED_0: ([u8; 0], [u8; 16]) = CTX.encrypt(&NONCE, &[], &[]);
+
+        assert_eq!(SEALED_0.1, [
+            0xdc, 0x20, 0xe2, 0xd8, 0x3f, 0x25, 0x70, 0x5b,
+            0xb4, 0x9e, 0x43, 0x9e, 0xca, 0x56, 0xde, 0x25
+        ]);
+
+        const SEALED_8: ([u8; 8], [u8; 16]) = CTX.encrypt(&NONCE, &[], &[1, 0, 0, 0, 0, 0, 0, 0]);
+
+        assert_eq!(SEALED_8.0, [0xb5, 0xd8, 0x39, 0x33, 0x0a, 0xc7, 0xb7, 0x86]);
+        assert_eq!(SEALED_8.1, [
+            0x57, 0x87, 0x82, 0xff, 0xf6, 0x01, 0x3b, 0x81,
+            0x5b, 0x28, 0x7c, 0x22, 0x49, 0x3a, 0x36, 0x4c
+        ]);
+
+        const SEALED_16: ([u8; 16], [u8; 16]) = CTX.encrypt(&NONCE, &[], &KEY);
+
+        assert_eq!(SEALED_16.0, [
+            0x74, 0x3f, 0x7c, 0x80, 0x77, 0xab, 0x25, 0xf8,
+            0x62, 0x4e, 0x2e, 0x94, 0x85, 0x79, 0xcf, 0x77
+        ]);
+        assert_eq!(SEALED_16.1, [
+            0x30, 0x3a, 0xaf, 0x90, 0xf6, 0xfe, 0x21, 0x19,
+            0x9c, 0x60, 0x68, 0x57, 0x74, 0x37, 0xa0, 0xc4
+        ]);
+
+        assert_eq!(CTX.decrypt(&NONCE, &[], &SEALED_16.0, &SEALED_16.1), Ok(KEY));
+    }
+
+    #[test]
+    fn test_aes128_gcm_siv_aad(){
+        //
+        // RFC 8452 appendix C.1, with one byte of aad
+        //
+        const PLAIN: [u8; 8] = [2, 0, 0, 0, 0, 0, 0, 0];
+        const SEALED: ([u8; 8], [u8; 16]) = CTX.encrypt(&NONCE, &[1], &PLAIN);
+
+        assert_eq!(SEALED.0, [0x1e, 0x6d, 0xab, 0xa3, 0x56, 0x69, 0xf4, 0x27]);
+        assert_eq!(SEALED.1, [
+            0x3b, 0x0a, 0x1a, 0x25, 0x60, 0x96, 0x9c, 0xdf,
+            0x79, 0x0d, 0x99, 0x75, 0x9a, 0xbd, 0x15, 0x08
+        ]);
+
+        assert_eq!(CTX.decrypt(&NONCE, &[1], &SEALED.0, &SEALED.1), Ok(PLAIN));
+        assert_eq!(CTX.decrypt(&NONCE, &[2], &SEALED.0, &SEALED.1), Err(Error::AuthenticationFailed));
+
+        let mut tag = SEALED.1;
+        tag[0] ^= 1;
+
+        assert_eq!(CTX.decrypt(&NONCE, &[1], &SEALED.0, &tag), Err(Error::AuthenticationFailed));
+    }
+}
@@ -0,0 +1,401 @@
+use crate::{Aes128KW, Aes192KW, Aes256KW, Error, PlainBuf, RoundKeys};
+use crate::aes::ecb::{read_block, write_block};
+use crate::utils::{bytes_to_array, constant_time_eq};
+use crate::utils::decrypt::equivalent_decrypt_block;
+use crate::utils::encrypt::encrypt_block;
+
+/// initial value of the key wrap
+const IV: [u8; 8] = [0xA6; 8];
+
+/// first half of the alternative initial value of the key wrap with padding
+const AIV_PREFIX: [u8; 4] = [0xA6, 0x59, 0x59, 0xA6];
+
+/// implement the key wrap contexts, which only differ by their key size
+macro_rules! impl_kw {
+    ($name:ident, $round_keys:literal, $key_length:literal) => {
+        impl $name {
+            pub const fn new(key: [u8; $key_length]) -> Self {
+                Self::with_round_keys(RoundKeys::<$round_keys>::new(key))
+            }
+
+            pub const fn with_round_keys(keys: RoundKeys<$round_keys>) -> Self {
+                Self {
+                    keys,
+                }
+            }
+
+            /// wrap a key of at least 16 bytes and a multiple of 8 bytes, `WRAPPED_LENGTH` is 8 bytes more
+            pub const fn key_wrap<const KEY_LENGTH: usize, const WRAPPED_LENGTH: usize>(
+                &self,
+                key: &[u8; KEY_LENGTH],
+            ) -> [u8; WRAPPED_LENGTH] {
+                key_wrap(&self.keys, key)
+            }
+
+            pub const fn key_unwrap<const WRAPPED_LENGTH: usize, const KEY_LENGTH: usize>(
+                &self,
+                wrapped: &[u8; WRAPPED_LENGTH],
+            ) -> Result<[u8; KEY_LENGTH], Error> {
+                key_unwrap(&self.keys, wrapped)
+            }
+
+            /// wrap a key of any length, `WRAPPED_LENGTH` is given by `padded_wrapped_length`
+            pub const fn key_wrap_padded<const KEY_LENGTH: usize, const WRAPPED_LENGTH: usize>(
+                &self,
+                key: &[u8; KEY_LENGTH],
+            ) -> [u8; WRAPPED_LENGTH] {
+                key_wrap_padded(&self.keys, key)
+            }
+
+            pub const fn key_unwrap_padded<const WRAPPED_LENGTH: usize>(
+                &self,
+                wrapped: &[u8; WRAPPED_LENGTH],
+            ) -> Result<PlainBuf<WRAPPED_LENGTH>, Error> {
+                key_unwrap_padded(&self.keys, wrapped)
+            }
+        }
+    };
+}
+
+impl_kw!(Aes128KW, 11, 16);
+impl_kw!(Aes192KW, 13, 24);
+impl_kw!(Aes256KW, 15, 32);
+
+/// length of a key wrapped with padding
+pub const fn padded_wrapped_length(key_length: usize) -> usize {
+    key_length.next_multiple_of(8) + 8
+}
+
+pub(crate) const fn key_wrap<const ROUND_KEYS: usize, const KEY_LENGTH: usize, const WRAPPED_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    key: &[u8; KEY_LENGTH],
+) -> [u8; WRAPPED_LENGTH] {
+
+    //
+    // check lengths
+    //
+    if KEY_LENGTH < 16 || !KEY_LENGTH.is_multiple_of(8) || WRAPPED_LENGTH != KEY_LENGTH + 8 {
+        panic!("Incorrect cipher length");
+    }
+
+    let mut wrapped = [0u8; WRAPPED_LENGTH];
+    copy_after_iv(&mut wrapped, &IV, key);
+
+    wrap(&keys.enc, &mut wrapped);
+    wrapped
+}
+
+pub(crate) const fn key_unwrap<const ROUND_KEYS: usize, const WRAPPED_LENGTH: usize, const KEY_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    wrapped: &[u8; WRAPPED_LENGTH],
+) -> Result<[u8; KEY_LENGTH], Error> {
+
+    //
+    // check lengths
+    //
+    if KEY_LENGTH < 16 || !KEY_LENGTH.is_multiple_of(8) || WRAPPED_LENGTH != KEY_LENGTH + 8 {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut data = *wrapped;
+    unwrap(&keys.dec, &mut data);
+
+    //
+    // integrity check on the initial value
+    //
+    if !constant_time_eq(&IV, data.split_at(8).0) {
+        return Err(Error::AuthenticationFailed);
+    }
+
+    Ok(bytes_to_array(data.split_at(8).1))
+}
+
+pub(crate) const fn key_wrap_padded<const ROUND_KEYS: usize, const KEY_LENGTH: usize, const WRAPPED_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    key: &[u8; KEY_LENGTH],
+) -> [u8; WRAPPED_LENGTH] {
+
+    //
+    // check lengths
+    //
+    if KEY_LENGTH == 0 || KEY_LENGTH > u32::MAX as usize || WRAPPED_LENGTH != padded_wrapped_length(KEY_LENGTH) {
+        panic!("Incorrect cipher length");
+    }
+
+    //
+    // the initial value holds the length of the key, the padding is made of zeros
+    //
+    let aiv = ((u32::from_be_bytes(AIV_PREFIX) as u64) << 32 | KEY_LENGTH as u64).to_be_bytes();
+
+    let mut wrapped = [0u8; WRAPPED_LENGTH];
+    copy_after_iv(&mut wrapped, &aiv, key);
+
+    //
+    // a single block is encrypted directly
+    //
+    if WRAPPED_LENGTH == 16 {
+        let block = encrypt_block(&read_block(&wrapped, 0), &keys.enc);
+        write_block(&mut wrapped, 0, &block);
+    } else {
+        wrap(&keys.enc, &mut wrapped);
+    }
+
+    wrapped
+}
+
+pub(crate) const fn key_unwrap_padded<const ROUND_KEYS: usize, const WRAPPED_LENGTH: usize>(
+    keys: &RoundKeys<ROUND_KEYS>,
+    wrapped: &[u8; WRAPPED_LENGTH],
+) -> Result<PlainBuf<WRAPPED_LENGTH>, Error> {
+
+    //
+    // check lengths
+    //
+    if WRAPPED_LENGTH < 16 || !WRAPPED_LENGTH.is_multiple_of(8) {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut data = *wrapped;
+
+    if WRAPPED_LENGTH == 16 {
+        let block = equivalent_decrypt_block(&read_block(&data, 0), &keys.dec);
+        write_block(&mut data, 0, &block);
+    } else {
+        unwrap(&keys.dec, &mut data);
+    }
+
+    //
+    // integrity check on the initial value, the length and the padding
+    //
+    let length = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    let padded = WRAPPED_LENGTH - 8;
+
+    let mut valid = constant_time_eq(&AIV_PREFIX, data.split_at(4).0)
+        && length > padded - 8
+        && length <= padded;
+
+    //
+    // the plain key is moved to the start of the buffer and the padding must be zeros
+    //
+    let mut key = [0u8; WRAPPED_LENGTH];
+    key.split_at_mut(padded).0.copy_from_slice(data.split_at(8).1);
+
+    let mut i = length;
+    while i < padded {
+        if key[i] != 0 {
+            valid = false;
+        }
+
+        i += 1;
+    }
+
+    if !valid {
+        return Err(Error::AuthenticationFailed);
+    }
+
+    Ok(PlainBuf::new(key, length))
+}
+
+/// write the initial value followed by the key, the rest of the buffer is left untouched
+const fn copy_after_iv(buf: &mut [u8], iv: &[u8; 8], key: &[u8]) {
+    let (head, tail) = buf.split_at_mut(8);
+
+    head.copy_from_slice(iv);
+    tail.split_at_mut(key.len()).0.copy_from_slice(key);
+}
+
+/// 64 bit register of the buffer at start, as a big endian integer
+const fn read_register(data: &[u8], start: usize) -> u64 {
+    u64::from_be_bytes(bytes_to_array(data.split_at(start).1))
+}
+
+const fn write_register(data: &mut [u8], start: usize, register: u64) {
+    data.split_at_mut(start).1.split_at_mut(8).0.copy_from_slice(&register.to_be_bytes());
+}
+
+///
+/// wrapping function W of SP 800-38F.
+///
+/// data holds the initial value A followed by the 64 bit registers R[1..n], the result
+/// replaces them in place.
+///
+const fn wrap<const ROUND_KEYS: usize>(keys: &[[u8; 16]; ROUND_KEYS], data: &mut [u8]) {
+    let n = data.len() / 8 - 1;
+    let mut a = read_register(data, 0);
+
+    let mut j = 0;
+    while j < 6 {
+        let mut i = 1;
+        while i <= n {
+
+            //
+            // B = AES(K, A | R[i])
+            //
+            let block = ((a as u128) << 64 | read_register(data, i * 8) as u128).to_be_bytes();
+            let block = u128::from_be_bytes(encrypt_block(&block, keys));
+
+            //
+            // A = MSB(64, B) ^ t and R[i] = LSB(64, B)
+            //
+            a = (block >> 64) as u64 ^ (n * j + i) as u64;
+            write_register(data, i * 8, block as u64);
+
+            i += 1;
+        }
+
+        j += 1;
+    }
+
+    write_register(data, 0, a);
+}
+
+/// unwrapping function W^-1 of SP 800-38F, the initial value is left in the first 8 bytes
+const fn unwrap<const ROUND_KEYS: usize>(dec_keys: &[[u8; 16]; ROUND_KEYS], data: &mut [u8]) {
+    let n = data.len() / 8 - 1;
+    let mut a = read_register(data, 0);
+
+    let mut j = 6;
+    while j > 0 {
+        j -= 1;
+
+        let mut i = n;
+        while i > 0 {
+
+            //
+            // B = AES-1(K, (A ^ t) | R[i])
+            //
+            let t = (n * j + i) as u64;
+
+            let block = (((a ^ t) as u128) << 64 | read_register(data, i * 8) as u128).to_be_bytes();
+            let block = u128::from_be_bytes(equivalent_decrypt_block(&block, dec_keys));
+
+            a = (block >> 64) as u64;
+            write_register(data, i * 8, block as u64);
+
+            i -= 1;
+        }
+    }
+
+    write_register(data, 0, a);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128KW, Aes192KW, Aes256KW, Error};
+    use super::padded_wrapped_length;
+
+    const KEK: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+        0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+        0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f
+    ];
+
+    const KEY_DATA: [u8; 32] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+        0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f
+    ];
+
+    #[test]
+    fn test_aes128_key_wrap(){
+        //
+        // RFC 3394 4.1
+        //
+        const EXPECTED: [u8; 24] = [
+            0x1f, 0xa6, 0x8b, 0x0a, 0x81, 0x12, 0xb4, 0x47,
+            0xae, 0xf3, 0x4b, 0xd8, 0xfb, 0x5a, 0x7b, 0x82,
+            0x9d, 0x3e, 0x86, 0x23, 0x71, 0xd2, 0xcf, 0xe5
+        ];
+
+        const CTX: Aes128KW = Aes128KW::new(*KEK.first_chunk().unwrap());
+        const WRAPPED: [u8; 24] = CTX.key_wrap::<16, 24>(KEY_DATA.first_chunk().unwrap());
+
+        assert_eq!(WRAPPED, EXPECTED);
+        assert_eq!(CTX.key_unwrap::<24, 16>(&WRAPPED), Ok(*KEY_DATA.first_chunk().unwrap()));
+
+        //
+        // any modification fails the integrity check
+        //
+        let mut wrapped = WRAPPED;
+        wrapped[23] ^= 1;
+
+        assert_eq!(CTX.key_unwrap::<24, 16>(&wrapped), Err(Error::AuthenticationFailed));
+        assert_eq!(CTX.key_unwrap::<24, 24>(&WRAPPED), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    fn test_aes256_key_wrap(){
+        //
+        // RFC 3394 4.6
+        //
+        const EXPECTED: [u8; 40] = [
+            0x28, 0xc9, 0xf4, 0x04, 0xc4, 0xb8, 0x10, 0xf4,
+            0xcb, 0xcc, 0xb3, 0x5c, 0xfb, 0x87, 0xf8, 0x26,
+            0x3f, 0x57, 0x86, 0xe2, 0xd8, 0x0e, 0xd3, 0x26,
+            0xcb, 0xc7, 0xf0, 0xe7, 0x1a, 0x99, 0xf4, 0x3b,
+            0xfb, 0x98, 0x8b, 0x9b, 0x7a, 0x02, 0xdd, 0x21
+        ];
+
+        const CTX: Aes256KW = Aes256KW::new(KEK);
+        const WRAPPED: [u8; 40] = CTX.key_wrap(&KEY_DATA);
+
+        assert_eq!(WRAPPED, EXPECTED);
+        assert_eq!(CTX.key_unwrap(&WRAPPED), Ok(KEY_DATA));
+    }
+
+    #[test]
+    fn test_aes192_key_wrap_padded(){
+        //
+        // RFC 5649 section 6
+        //
+        const KEK: [u8; 24] = [
+            0x58, 0x40, 0xdf, 0x6e, 0x29, 0xb0, 0x2a, 0xf1,
+            0xab, 0x49, 0x3b, 0x70, 0x5b, 0xf1, 0x6e, 0xa1,
+            0xae, 0x83, 0x38, 0xf4, 0xdc, 0xc1, 0x76, 0xa8
+        ];
+
+        const KEY_20: [u8; 20] = [
+            0xc3, 0x7b, 0x7e, 0x64, 0x92, 0x58, 0x43, 0x40,
+            0xbe, 0xd1, 0x22, 0x07, 0x80, 0x89, 0x41, 0x15,
+            0x50, 0x68, 0xf7, 0x38
+        ];
+
+        const EXPECTED_20: [u8; 32] = [
+            0x13, 0x8b, 0xde, 0xaa, 0x9b, 0x8f, 0xa7, 0xfc,
+            0x61, 0xf9, 0x77, 0x42, 0xe7, 0x22, 0x48, 0xee,
+            0x5a, 0xe6, 0xae, 0x53, 0x60, 0xd1, 0xae, 0x6a,
+            0x5f, 0x54, 0xf3, 0x73, 0xfa, 0x54, 0x3b, 0x6a
+        ];
+
+        const KEY_7: [u8; 7] = [0x46, 0x6f, 0x72, 0x50, 0x61, 0x73, 0x69];
+
+        const EXPECTED_7: [u8; 16] = [
+            0xaf, 0xbe, 0xb0, 0xf0, 0x7d, 0xfb, 0xf5, 0x41,
+            0x92, 0x00, 0xf2, 0xcc, 0xb5, 0x0b, 0xb2, 0x4f
+        ];
+
+        const CTX: Aes192KW = Aes192KW::new(KEK);
+        const WRAPPED_20: [u8; padded_wrapped_length(20)] = CTX.key_wrap_padded(&KEY_20);
+        const WRAPPED_7: [u8; padded_wrapped_length(7)] = CTX.key_wrap_padded(&KEY_7);
+
+        assert_eq!(WRAPPED_20, EXPECTED_20);
+        assert_eq!(WRAPPED_7, EXPECTED_7);
+
+        assert_eq!(CTX.key_unwrap_padded(&WRAPPED_20).unwrap().as_slice(), KEY_20);
+        assert_eq!(CTX.key_unwrap_padded(&WRAPPED_7).unwrap().as_slice(), KEY_7);
+
+        let mut wrapped = WRAPPED_7;
+        wrapped[0] ^= 1;
+
+        assert_eq!(CTX.key_unwrap_padded(&wrapped).unwrap_err(), Error::AuthenticationFailed);
+        assert_eq!(CTX.key_unwrap_padded(&[0u8; 12]).unwrap_err(), Error::InvalidLength);
+
+        //
+        // a plain key wrap is not a valid padded one
+        //
+        let wrapped: [u8; 32] = CTX.key_wrap(&[7u8; 24]);
+        assert_eq!(CTX.key_unwrap_padded(&wrapped).unwrap_err(), Error::AuthenticationFailed);
+    }
+}
@@ -1,2 +1,13 @@
 pub mod cbc;
-pub mod ecb;
\ No newline at end of file
+pub mod ccm;
+pub mod cfb;
+pub mod cmac;
+pub mod ctr;
+pub mod ecb;
+pub mod gcm;
+pub mod gcm_siv;
+pub mod kw;
+pub mod ofb;
+pub mod siv;
+pub mod stream;
+pub mod xts;
@@ -0,0 +1,124 @@
+use crate::{Aes128OFB, RoundKeys};
+use crate::utils::encrypt::encrypt_block;
+
+impl Aes128OFB {
+    pub const fn new(key: [u8; 16], iv: [u8; 16]) -> Self {
+        Self::with_round_keys(RoundKeys::<11>::new(key), iv)
+    }
+
+    pub const fn with_round_keys(keys: RoundKeys<11>, iv: [u8; 16]) -> Self {
+        Self {
+            keys,
+            iv,
+        }
+    }
+
+    pub const fn encrypt<const LENGTH: usize>(
+        &self,
+        plain: &[u8; LENGTH],
+    ) -> [u8; LENGTH] {
+        let mut cipher = *plain;
+        self.apply_keystream(&mut cipher);
+
+        cipher
+    }
+
+    pub const fn decrypt<const LENGTH: usize>(
+        &self,
+        cipher: &[u8; LENGTH],
+    ) -> [u8; LENGTH] {
+        let mut plain = *cipher;
+        self.apply_keystream(&mut plain);
+
+        plain
+    }
+
+    /// xor the keystream into data, encryption and decryption are the same operation
+    pub const fn apply_keystream(&self, data: &mut [u8]) {
+        apply_keystream(&self.keys.enc, &self.iv, data)
+    }
+}
+
+pub(crate) const fn apply_keystream<const ROUND_KEYS: usize>(
+    keys: &[[u8; 16]; ROUND_KEYS],
+    iv: &[u8; 16],
+    data: &mut [u8],
+) {
+    let mut keystream = *iv;
+
+    let mut i = 0;
+
+    while i < data.len() {
+
+        //
+        // each keystream block is the encryption of the previous one
+        //
+        if i % 16 == 0 {
+            keystream = encrypt_block(&keystream, keys);
+        }
+
+        data[i] ^= keystream[i % 16];
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128OFB, RoundKeys};
+
+    const KEY: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+        0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c
+    ];
+
+    const IV: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f
+    ];
+
+    const PLAIN: [u8; 64] = [
+        0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96,
+        0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+        0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c,
+        0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+        0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11,
+        0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+        0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17,
+        0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10
+    ];
+
+    //
+    // SP 800-38A F.4.1
+    //
+    const EXPECTED: [u8; 64] = [
+        0x3b, 0x3f, 0xd9, 0x2e, 0xb7, 0x2d, 0xad, 0x20,
+        0x33, 0x34, 0x49, 0xf8, 0xe8, 0x3c, 0xfb, 0x4a,
+        0x77, 0x89, 0x50, 0x8d, 0x16, 0x91, 0x8f, 0x03,
+        0xf5, 0x3c, 0x52, 0xda, 0xc5, 0x4e, 0xd8, 0x25,
+        0x97, 0x40, 0x05, 0x1e, 0x9c, 0x5f, 0xec, 0xf6,
+        0x43, 0x44, 0xf7, 0xa8, 0x22, 0x60, 0xed, 0xcc,
+        0x30, 0x4c, 0x65, 0x28, 0xf6, 0x59, 0xc7, 0x78,
+        0x66, 0xa5, 0x10, 0xd9, 0xc1, 0xd6, 0xae, 0x5e
+    ];
+
+    #[test]
+    fn test_encrypt_decrypt_aes128_ofb(){
+        const CTX: Aes128OFB = Aes128OFB::new(KEY, IV);
+        const CIPHER: [u8; 64] = CTX.encrypt(&PLAIN);
+
+        assert_eq!(CIPHER, EXPECTED);
+        assert_eq!(CTX.decrypt(&CIPHER), PLAIN);
+        assert_eq!(Aes128OFB::with_round_keys(RoundKeys::<11>::new(KEY), IV).encrypt(&PLAIN), EXPECTED);
+    }
+
+    #[test]
+    fn test_encrypt_aes128_ofb_partial_block(){
+        const CTX: Aes128OFB = Aes128OFB::new(KEY, IV);
+
+        let mut data = [0u8; 21];
+        data.copy_from_slice(&PLAIN[..21]);
+        CTX.apply_keystream(&mut data);
+
+        assert_eq!(data, EXPECTED[..21]);
+    }
+}
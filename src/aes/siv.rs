@@ -0,0 +1,259 @@
+use crate::{Aes128CMAC, Aes128SIV, Error, RoundKeys};
+use crate::aes::cmac::double;
+use crate::aes::ctr::{apply_keystream, Increment};
+use crate::utils::constant_time_eq;
+
+/// most aad components S2V accepts, the plain text being the last of its 127 inputs
+pub const MAX_AAD_COMPONENTS: usize = 126;
+
+impl Aes128SIV {
+    pub const fn new(key: [u8; 32]) -> Self {
+        let mut mac_key = [0u8; 16];
+        let mut ctr_key = [0u8; 16];
+
+        let mut i = 0;
+        while i < 16 {
+            mac_key[i] = key[i];
+            ctr_key[i] = key[i + 16];
+            i += 1;
+        }
+
+        Self::with_round_keys(RoundKeys::<11>::new(mac_key), RoundKeys::<11>::new(ctr_key))
+    }
+
+    /// the round keys of the first half of the key, used by S2V, and of the second half, used by CTR
+    pub const fn with_round_keys(mac_keys: RoundKeys<11>, keys: RoundKeys<11>) -> Self {
+        Self {
+            mac: Aes128CMAC::with_round_keys(mac_keys),
+            keys,
+        }
+    }
+
+    ///
+    /// encrypt and authenticate, returns the synthetic iv and the cipher.
+    ///
+    /// Each aad component is authenticated separately. Without a nonce among them the
+    /// encryption is deterministic, so equal plain texts give equal ciphers. Panics with more
+    /// than `MAX_AAD_COMPONENTS` components.
+    ///
+    pub const fn encrypt<const LENGTH: usize>(
+        &self,
+        aad: &[&[u8]],
+        plain: &[u8; LENGTH],
+    ) -> ([u8; 16], [u8; LENGTH]) {
+        if aad.len() > MAX_AAD_COMPONENTS {
+            panic!("Too many aad components");
+        }
+
+        let siv = s2v(&self.mac, aad, plain);
+
+        let mut cipher = *plain;
+        apply_keystream(&self.keys.enc, &counter(&siv), Increment::Counter128, &mut cipher);
+
+        (siv, cipher)
+    }
+
+    /// decrypt and check the synthetic iv, nothing is returned unless the cipher is authentic
+    pub const fn decrypt<const LENGTH: usize>(
+        &self,
+        aad: &[&[u8]],
+        siv: &[u8; 16],
+        cipher: &[u8; LENGTH],
+    ) -> Result<[u8; LENGTH], Error> {
+        if aad.len() > MAX_AAD_COMPONENTS {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut plain = *cipher;
+        apply_keystream(&self.keys.enc, &counter(siv), Increment::Counter128, &mut plain);
+
+        if !constant_time_eq(&s2v(&self.mac, aad, &plain), siv) {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        Ok(plain)
+    }
+}
+
+/// the counter starts from the synthetic iv with the top bit of its 3rd and 4th words cleared
+const fn counter(siv: &[u8; 16]) -> [u8; 16] {
+    let mut counter = *siv;
+    counter[8] &= 0x7f;
+    counter[12] &= 0x7f;
+
+    counter
+}
+
+/// S2V of RFC 5297 over the aad components followed by the plain text
+const fn s2v(mac: &Aes128CMAC, aad: &[&[u8]], plain: &[u8]) -> [u8; 16] {
+    let mut d = u128::from_be_bytes(mac.mac(&[0u8; 16]));
+
+    let mut i = 0;
+    while i < aad.len() {
+        d = double(d) ^ u128::from_be_bytes(mac.mac(aad[i]));
+        i += 1;
+    }
+
+    let mut state = mac.state();
+
+    if plain.len() >= 16 {
+
+        //
+        // xor D into the last 16 bytes of the plain text
+        //
+        let (head, tail) = plain.split_at(plain.len() - 16);
+        let tail = u128::from_be_bytes(*tail.first_chunk().unwrap()) ^ d;
+
+        state.update(head);
+        state.update(&tail.to_be_bytes());
+    } else {
+
+        //
+        // pad the plain text with 0x80 0x00.. and xor it with dbl(D)
+        //
+        let mut last = [0u8; 16];
+
+        let mut k = 0;
+        while k < plain.len() {
+            last[k] = plain[k];
+            k += 1;
+        }
+
+        last[plain.len()] = 0x80;
+
+        let last = u128::from_be_bytes(last) ^ double(d);
+        state.update(&last.to_be_bytes());
+    }
+
+    state.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128SIV, Error};
+    use super::MAX_AAD_COMPONENTS;
+
+    #[test]
+    fn test_aes128_siv_deterministic(){
+        //
+        // RFC 5297 A.1
+        //
+        const KEY: [u8; 32] = [
+            0xff, 0xfe, 0xfd, 0xfc, 0xfb, 0xfa, 0xf9, 0xf8,
+            0xf7, 0xf6, 0xf5, 0xf4, 0xf3, 0xf2, 0xf1, 0xf0,
+            0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7,
+            0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff
+        ];
+
+        const AAD: [u8; 24] = [
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+            0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27
+        ];
+
+        const PLAIN: [u8; 14] = [
+            0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88,
+            0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee
+        ];
+
+        const EXPECTED_SIV: [u8; 16] = [
+            0x85, 0x63, 0x2d, 0x07, 0xc6, 0xe8, 0xf3, 0x7f,
+            0x95, 0x0a, 0xcd, 0x32, 0x0a, 0x2e, 0xcc, 0x93
+        ];
+
+        const EXPECTED: [u8; 14] = [
+            0x40, 0xc0, 0x2b, 0x96, 0x90, 0xc4, 0xdc, 0x04,
+            0xda, 0xef, 0x7f, 0x6a, 0xfe, 0x5c
+        ];
+
+        const CTX: Aes128SIV = Aes128SIV::new(KEY);
+        const SEALED: ([u8; 16], [u8; 14]) = CTX.encrypt(&[&AAD], &PLAIN);
+
+        assert_eq!(SEALED, (EXPECTED_SIV, EXPECTED));
+        assert_eq!(CTX.decrypt(&[&AAD], &SEALED.0, &SEALED.1), Ok(PLAIN));
+
+        let mut cipher = SEALED.1;
+        cipher[0] ^= 1;
+
+        assert_eq!(CTX.decrypt(&[&AAD], &SEALED.0, &cipher), Err(Error::AuthenticationFailed));
+        assert_eq!(CTX.decrypt(&[], &SEALED.0, &SEALED.1), Err(Error::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_aes128_siv_nonce_based(){
+        //
+        // RFC 5297 A.2, the nonce is the last aad component
+        //
+        const KEY: [u8; 32] = [
+            0x7f, 0x7e, 0x7d, 0x7c, 0x7b, 0x7a, 0x79, 0x78,
+            0x77, 0x76, 0x75, 0x74, 0x73, 0x72, 0x71, 0x70,
+            0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47,
+            0x48, 0x49, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e, 0x4f
+        ];
+
+        const AAD_1: [u8; 40] = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+            0xde, 0xad, 0xda, 0xda, 0xde, 0xad, 0xda, 0xda,
+            0xff, 0xee, 0xdd, 0xcc, 0xbb, 0xaa, 0x99, 0x88,
+            0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, 0x00
+        ];
+
+        const AAD_2: [u8; 10] = [
+            0x10, 0x20, 0x30, 0x40, 0x50, 0x60, 0x70, 0x80,
+            0x90, 0xa0
+        ];
+
+        const NONCE: [u8; 16] = [
+            0x09, 0xf9, 0x11, 0x02, 0x9d, 0x74, 0xe3, 0x5b,
+            0xd8, 0x41, 0x56, 0xc5, 0x63, 0x56, 0x88, 0xc0
+        ];
+
+        const PLAIN: [u8; 47] = *b"this is some plaintext to encrypt using SIV-AES";
+
+        const EXPECTED_SIV: [u8; 16] = [
+            0x7b, 0xdb, 0x6e, 0x3b, 0x43, 0x26, 0x67, 0xeb,
+            0x06, 0xf4, 0xd1, 0x4b, 0xff, 0x2f, 0xbd, 0x0f
+        ];
+
+        const EXPECTED: [u8; 47] = [
+            0xcb, 0x90, 0x0f, 0x2f, 0xdd, 0xbe, 0x40, 0x43,
+            0x26, 0x60, 0x19, 0x65, 0xc8, 0x89, 0xbf, 0x17,
+            0xdb, 0xa7, 0x7c, 0xeb, 0x09, 0x4f, 0xa6, 0x63,
+            0xb7, 0xa3, 0xf7, 0x48, 0xba, 0x8a, 0xf8, 0x29,
+            0xea, 0x64, 0xad, 0x54, 0x4a, 0x27, 0x2e, 0x9c,
+            0x48, 0x5b, 0x62, 0xa3, 0xfd, 0x5c, 0x0d
+        ];
+
+        const CTX: Aes128SIV = Aes128SIV::new(KEY);
+        const SEALED: ([u8; 16], [u8; 47]) = CTX.encrypt(&[&AAD_1, &AAD_2, &NONCE], &PLAIN);
+
+        assert_eq!(SEALED, (EXPECTED_SIV, EXPECTED));
+        assert_eq!(CTX.decrypt(&[&AAD_1, &AAD_2, &NONCE], &SEALED.0, &SEALED.1), Ok(PLAIN));
+
+        //
+        // the order of the components matters
+        //
+        assert_eq!(
+            CTX.decrypt(&[&AAD_2, &AAD_1, &NONCE], &SEALED.0, &SEALED.1),
+            Err(Error::AuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn test_aes128_siv_aad_components(){
+        const CTX: Aes128SIV = Aes128SIV::new([0x42; 32]);
+
+        let aad = [&[0u8; 4][..]; MAX_AAD_COMPONENTS + 1];
+        let (siv, cipher) = CTX.encrypt(&aad[..MAX_AAD_COMPONENTS], b"plain");
+
+        assert_eq!(CTX.decrypt(&aad[..MAX_AAD_COMPONENTS], &siv, &cipher), Ok(*b"plain"));
+        assert_eq!(CTX.decrypt(&aad, &siv, &cipher), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many aad components")]
+    fn test_aes128_siv_too_many_aad_components(){
+        Aes128SIV::new([0x42; 32]).encrypt(&[&[0u8; 4][..]; MAX_AAD_COMPONENTS + 1], b"plain");
+    }
+}
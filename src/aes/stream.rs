@@ -0,0 +1,451 @@
+//!
+//! Incremental encryption and decryption of data fed in several parts.
+//!
+//! `update` writes the complete blocks available so far to the output and buffers the rest,
+//! `finalize` handles the padding of the last block. The output must have room for the bytes
+//! written, as returned by `update_length`, otherwise nothing is written and
+//! `Error::InvalidLength` is returned.
+//!
+
+use crate::{CbcDecryptor, CbcEncryptor, EcbDecryptor, EcbEncryptor, Error, RoundKeys};
+use crate::aes::{cbc, ecb};
+use crate::aes::ecb::write_block;
+use crate::padding::Padding;
+use core::marker::PhantomData;
+
+impl<const ROUND_KEYS: usize, P: Padding> EcbEncryptor<ROUND_KEYS, P> {
+    pub(crate) const fn new(keys: RoundKeys<ROUND_KEYS>) -> Self {
+        Self {
+            keys,
+            buffer: [0u8; 16],
+            buffered: 0,
+            padding: PhantomData,
+        }
+    }
+
+    /// number of bytes written by an update with input_len bytes
+    pub const fn update_length(&self, input_len: usize) -> usize {
+        update_length(self.buffered, input_len, false)
+    }
+
+    pub const fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if output.len() < self.update_length(input.len()) {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut written = 0;
+
+        let mut i = 0;
+        while i < input.len() {
+            self.buffer[self.buffered] = input[i];
+            self.buffered += 1;
+
+            if self.buffered == 16 {
+                ecb::encrypt_blocks(&self.keys, &mut self.buffer);
+                write_block(output, written, &self.buffer);
+
+                written += 16;
+                self.buffered = 0;
+            }
+
+            i += 1;
+        }
+
+        Ok(written)
+    }
+
+    ///
+    /// pad and encrypt the buffered bytes, the output needs room for a block.
+    ///
+    /// Without padding the data must be block aligned, otherwise `Error::InvalidLength` is returned.
+    ///
+    pub const fn finalize(mut self, output: &mut [u8]) -> Result<usize, Error> {
+        let length = P::SCHEME.padded_length(self.buffered);
+
+        if output.len() < length || !length.is_multiple_of(16) {
+            return Err(Error::InvalidLength);
+        }
+
+        if length > 0 {
+            P::SCHEME.pad(&mut self.buffer, self.buffered);
+            ecb::encrypt_blocks(&self.keys, &mut self.buffer);
+            write_block(output, 0, &self.buffer);
+        }
+
+        Ok(length)
+    }
+}
+
+impl<const ROUND_KEYS: usize, P: Padding> EcbDecryptor<ROUND_KEYS, P> {
+    pub(crate) const fn new(keys: RoundKeys<ROUND_KEYS>) -> Self {
+        Self {
+            keys,
+            buffer: [0u8; 16],
+            buffered: 0,
+            padding: PhantomData,
+        }
+    }
+
+    /// number of bytes written by an update with input_len bytes
+    pub const fn update_length(&self, input_len: usize) -> usize {
+        update_length(self.buffered, input_len, true)
+    }
+
+    pub const fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if output.len() < self.update_length(input.len()) {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut written = 0;
+
+        let mut i = 0;
+        while i < input.len() {
+
+            //
+            // a full block is only decrypted once more data follows it
+            //
+            if self.buffered == 16 {
+                ecb::decrypt_blocks(&self.keys, &mut self.buffer);
+                write_block(output, written, &self.buffer);
+
+                written += 16;
+                self.buffered = 0;
+            }
+
+            self.buffer[self.buffered] = input[i];
+            self.buffered += 1;
+            i += 1;
+        }
+
+        Ok(written)
+    }
+
+    /// decrypt the last block and remove the padding, the output needs room for a block
+    pub const fn finalize(mut self, output: &mut [u8]) -> Result<usize, Error> {
+        if self.buffered != 0 && self.buffered != 16 {
+            return Err(Error::InvalidLength);
+        }
+
+        let (last, _) = self.buffer.split_at_mut(self.buffered);
+        ecb::decrypt_blocks(&self.keys, last);
+
+        finish(last, P::SCHEME.unpadded_length(last), output)
+    }
+}
+
+impl<const ROUND_KEYS: usize, P: Padding> CbcEncryptor<ROUND_KEYS, P> {
+    pub(crate) const fn new(keys: RoundKeys<ROUND_KEYS>, iv: [u8; 16]) -> Self {
+        Self {
+            keys,
+            prev: iv,
+            buffer: [0u8; 16],
+            buffered: 0,
+            padding: PhantomData,
+        }
+    }
+
+    /// number of bytes written by an update with input_len bytes
+    pub const fn update_length(&self, input_len: usize) -> usize {
+        update_length(self.buffered, input_len, false)
+    }
+
+    pub const fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if output.len() < self.update_length(input.len()) {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut written = 0;
+
+        let mut i = 0;
+        while i < input.len() {
+            self.buffer[self.buffered] = input[i];
+            self.buffered += 1;
+
+            if self.buffered == 16 {
+                cbc::encrypt_blocks(&self.keys, &mut self.prev, &mut self.buffer);
+                write_block(output, written, &self.buffer);
+
+                written += 16;
+                self.buffered = 0;
+            }
+
+            i += 1;
+        }
+
+        Ok(written)
+    }
+
+    ///
+    /// pad and encrypt the buffered bytes, the output needs room for a block.
+    ///
+    /// Without padding the data must be block aligned, otherwise `Error::InvalidLength` is returned.
+    ///
+    pub const fn finalize(mut self, output: &mut [u8]) -> Result<usize, Error> {
+        let length = P::SCHEME.padded_length(self.buffered);
+
+        if output.len() < length || !length.is_multiple_of(16) {
+            return Err(Error::InvalidLength);
+        }
+
+        if length > 0 {
+            P::SCHEME.pad(&mut self.buffer, self.buffered);
+            cbc::encrypt_blocks(&self.keys, &mut self.prev, &mut self.buffer);
+            write_block(output, 0, &self.buffer);
+        }
+
+        Ok(length)
+    }
+}
+
+impl<const ROUND_KEYS: usize, P: Padding> CbcDecryptor<ROUND_KEYS, P> {
+    pub(crate) const fn new(keys: RoundKeys<ROUND_KEYS>, iv: [u8; 16]) -> Self {
+        Self {
+            keys,
+            prev: iv,
+            buffer: [0u8; 16],
+            buffered: 0,
+            padding: PhantomData,
+        }
+    }
+
+    /// number of bytes written by an update with input_len bytes
+    pub const fn update_length(&self, input_len: usize) -> usize {
+        update_length(self.buffered, input_len, true)
+    }
+
+    pub const fn update(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize, Error> {
+        if output.len() < self.update_length(input.len()) {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut written = 0;
+
+        let mut i = 0;
+        while i < input.len() {
+
+            //
+            // a full block is only decrypted once more data follows it
+            //
+            if self.buffered == 16 {
+                cbc::decrypt_blocks(&self.keys, &mut self.prev, &mut self.buffer);
+                write_block(output, written, &self.buffer);
+
+                written += 16;
+                self.buffered = 0;
+            }
+
+            self.buffer[self.buffered] = input[i];
+            self.buffered += 1;
+            i += 1;
+        }
+
+        Ok(written)
+    }
+
+    /// decrypt the last block and remove the padding, the output needs room for a block
+    pub const fn finalize(mut self, output: &mut [u8]) -> Result<usize, Error> {
+        if self.buffered != 0 && self.buffered != 16 {
+            return Err(Error::InvalidLength);
+        }
+
+        let (last, _) = self.buffer.split_at_mut(self.buffered);
+        cbc::decrypt_blocks(&self.keys, &mut self.prev, last);
+
+        finish(last, P::SCHEME.unpadded_length(last), output)
+    }
+}
+
+/// number of complete blocks written by an update, decryptors always keep the last block
+const fn update_length(buffered: usize, input_len: usize, hold_back: bool) -> usize {
+    if !hold_back {
+        (buffered + input_len) / 16 * 16
+    } else if input_len == 0 {
+        0
+    } else {
+        (buffered + input_len - 1) / 16 * 16
+    }
+}
+
+/// copy the unpadded plain text of the last block to the output
+const fn finish(last: &[u8], length: Result<usize, Error>, output: &mut [u8]) -> Result<usize, Error> {
+    let length = match length {
+        Ok(length) => length,
+        Err(err) => return Err(err),
+    };
+
+    if output.len() < length {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut i = 0;
+    while i < length {
+        output[i] = last[i];
+        i += 1;
+    }
+
+    Ok(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128CBC, Aes256ECB, Error};
+    use crate::padding::{NoPadding, ZeroPadding};
+
+    const PLAIN: [u8; 100] = {
+        let mut plain = [0u8; 100];
+
+        let mut i = 0;
+        while i < 100 {
+            plain[i] = i as u8;
+            i += 1;
+        }
+
+        plain
+    };
+
+    #[test]
+    fn test_cbc_stream(){
+        const CTX: Aes128CBC = Aes128CBC::new([0x2b; 16], [0x0f; 16]);
+        const CIPHER: [u8; 112] = CTX.encrypt::<100, 112>(&PLAIN);
+
+        for chunk in [1, 7, 16, 33, 100] {
+
+            //
+            // encrypt in chunks
+            //
+            let mut encryptor = CTX.encryptor();
+            let mut cipher = [0u8; 112];
+            let mut written = 0;
+
+            for part in PLAIN.chunks(chunk) {
+                written += encryptor.update(part, &mut cipher[written..]).unwrap();
+            }
+
+            written += encryptor.finalize(&mut cipher[written..]).unwrap();
+
+            assert_eq!(written, 112);
+            assert_eq!(cipher, CIPHER);
+
+            //
+            // decrypt in chunks
+            //
+            let mut decryptor = CTX.decryptor();
+            let mut plain = [0u8; 112];
+            let mut written = 0;
+
+            for part in CIPHER.chunks(chunk) {
+                written += decryptor.update(part, &mut plain[written..]).unwrap();
+            }
+
+            assert_eq!(written, 96);
+
+            written += decryptor.finalize(&mut plain[written..]).unwrap();
+
+            assert_eq!(written, 100);
+            assert_eq!(plain[..100], PLAIN);
+        }
+    }
+
+    #[test]
+    fn test_ecb_stream(){
+        const CTX: Aes256ECB<ZeroPadding> = Aes256ECB::with_padding([0x60; 32], ZeroPadding);
+        const CIPHER: [u8; 112] = CTX.encrypt::<100, 112>(&PLAIN);
+
+        let mut encryptor = CTX.encryptor();
+        let mut cipher = [0u8; 112];
+
+        let written = encryptor.update(&PLAIN[..40], &mut cipher).unwrap();
+        let written = written + encryptor.update(&PLAIN[40..], &mut cipher[written..]).unwrap();
+
+        assert_eq!(encryptor.finalize(&mut cipher[written..]), Ok(16));
+        assert_eq!(cipher, CIPHER);
+
+        let mut decryptor = CTX.decryptor();
+        let mut plain = [0u8; 112];
+
+        let written = decryptor.update(&CIPHER, &mut plain).unwrap();
+        let written = written + decryptor.finalize(&mut plain[written..]).unwrap();
+
+        assert_eq!(plain[..written], PLAIN);
+    }
+
+    #[test]
+    fn test_stream_errors(){
+        const CTX: Aes128CBC = Aes128CBC::new([0x2b; 16], [0x0f; 16]);
+
+        //
+        // the output must have room for the complete blocks
+        //
+        let mut encryptor = CTX.encryptor();
+        assert_eq!(encryptor.update_length(20), 16);
+        assert_eq!(encryptor.update(&[0u8; 20], &mut [0u8; 15]), Err(Error::InvalidLength));
+
+        let mut decryptor = CTX.decryptor();
+        assert_eq!(decryptor.update_length(32), 16);
+        assert_eq!(decryptor.update(&[0u8; 32], &mut [0u8; 15]), Err(Error::InvalidLength));
+
+        //
+        // a truncated or empty cipher is rejected on finalize
+        //
+        let mut decryptor = CTX.decryptor();
+        decryptor.update(&[0u8; 20], &mut [0u8; 16]).unwrap();
+
+        assert_eq!(decryptor.finalize(&mut [0u8; 16]), Err(Error::InvalidLength));
+        assert_eq!(CTX.decryptor().finalize(&mut []), Err(Error::InvalidLength));
+
+        //
+        // the padding of the last block is checked
+        //
+        let mut cipher = [0u8; 16];
+        let mut encryptor = Aes128CBC::with_padding([0x2b; 16], [0x0f; 16], NoPadding).encryptor();
+        encryptor.update(&[0u8; 16], &mut cipher).unwrap();
+
+        let mut decryptor = CTX.decryptor();
+        decryptor.update(&cipher, &mut []).unwrap();
+
+        assert_eq!(decryptor.finalize(&mut [0u8; 16]), Err(Error::InvalidPadding));
+
+        //
+        // unaligned data is rejected without padding
+        //
+        let mut encryptor = Aes128CBC::with_padding([0x2b; 16], [0x0f; 16], NoPadding).encryptor();
+        encryptor.update(&[0u8; 19], &mut cipher).unwrap();
+
+        assert_eq!(encryptor.finalize(&mut [0u8; 16]), Err(Error::InvalidLength));
+    }
+
+    #[test]
+    fn test_zero_padding_stream_matches_one_shot(){
+        const CTX: Aes128CBC<ZeroPadding> = Aes128CBC::with_padding([0x2b; 16], [0x0f; 16], ZeroPadding);
+
+        //
+        // the trailing zeros of the plain text cross a block boundary
+        //
+        const PLAIN: [u8; 30] = {
+            let mut plain = [0u8; 30];
+
+            let mut i = 0;
+            while i < 10 {
+                plain[i] = 1;
+                i += 1;
+            }
+
+            plain
+        };
+
+        const CIPHER: [u8; 32] = CTX.encrypt::<30, 32>(&PLAIN);
+
+        let one_shot = CTX.decrypt_to_array(&CIPHER).unwrap();
+
+        let mut decryptor = CTX.decryptor();
+        let mut plain = [0u8; 32];
+        let mut written = decryptor.update(&CIPHER, &mut plain).unwrap();
+        written += decryptor.finalize(&mut plain[written..]).unwrap();
+
+        //
+        // both only remove the zeros of the last block
+        //
+        assert_eq!(one_shot.as_slice(), &plain[..written]);
+        assert_eq!(one_shot.as_slice(), &PLAIN[..16]);
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{Aes128XTS, Error, RoundKeys};
+use crate::aes::ecb::{read_block, write_block};
+use crate::utils::decrypt::equivalent_decrypt_block;
+use crate::utils::encrypt::encrypt_block;
+
+impl Aes128XTS {
+    pub const fn new(key: [u8; 32]) -> Self {
+        let mut data_key = [0u8; 16];
+        let mut tweak_key = [0u8; 16];
+
+        let mut i = 0;
+        while i < 16 {
+            data_key[i] = key[i];
+            tweak_key[i] = key[i + 16];
+            i += 1;
+        }
+
+        Self::with_round_keys(RoundKeys::<11>::new(data_key), RoundKeys::<11>::new(tweak_key))
+    }
+
+    /// the round keys of the first and second half of the key
+    pub const fn with_round_keys(keys: RoundKeys<11>, tweak_keys: RoundKeys<11>) -> Self {
+        Self {
+            keys,
+            tweak_keys,
+        }
+    }
+
+    ///
+    /// encrypt a sector of at least 16 bytes in place, a partial last block is handled by ciphertext stealing.
+    ///
+    /// Shorter sectors are rejected with `Error::InvalidLength` and left untouched.
+    ///
+    pub const fn encrypt_sector(&self, sector: u128, data: &mut [u8]) -> Result<(), Error> {
+        xts(&self.keys, &self.tweak_keys, sector, data, false)
+    }
+
+    pub const fn decrypt_sector(&self, sector: u128, data: &mut [u8]) -> Result<(), Error> {
+        xts(&self.keys, &self.tweak_keys, sector, data, true)
+    }
+
+    ///
+    /// encrypt consecutive sectors starting with `first_sector`, the last one may be shorter.
+    ///
+    /// Every sector, the last one included, must be at least 16 bytes long. Otherwise
+    /// `Error::InvalidLength` is returned and nothing is encrypted.
+    ///
+    pub const fn encrypt_sectors(&self, data: &mut [u8], sector_size: usize, first_sector: u128) -> Result<(), Error> {
+        sectors(self, data, sector_size, first_sector, false)
+    }
+
+    pub const fn decrypt_sectors(&self, data: &mut [u8], sector_size: usize, first_sector: u128) -> Result<(), Error> {
+        sectors(self, data, sector_size, first_sector, true)
+    }
+
+    ///
+    /// encrypt a single sector of a buffer made of consecutive sectors starting with sector 0.
+    ///
+    /// `Error::InvalidLength` is returned if the sector is not in the buffer or is shorter than 16 bytes.
+    ///
+    pub const fn encrypt_sector_at(&self, data: &mut [u8], sector_size: usize, sector: u128) -> Result<(), Error> {
+        match sector_slice(data, sector_size, sector) {
+            Ok(data) => xts(&self.keys, &self.tweak_keys, sector, data, false),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub const fn decrypt_sector_at(&self, data: &mut [u8], sector_size: usize, sector: u128) -> Result<(), Error> {
+        match sector_slice(data, sector_size, sector) {
+            Ok(data) => xts(&self.keys, &self.tweak_keys, sector, data, true),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// bytes of the given sector, the last sector of the buffer may be shorter
+const fn sector_slice(data: &mut [u8], sector_size: usize, sector: u128) -> Result<&mut [u8], Error> {
+
+    //
+    // the start of the sector must fit in the buffer without truncating or overflowing
+    //
+    if sector_size == 0 || sector > usize::MAX as u128 {
+        return Err(Error::InvalidLength);
+    }
+
+    let start = match (sector as usize).checked_mul(sector_size) {
+        Some(start) if start < data.len() => start,
+        _ => return Err(Error::InvalidLength),
+    };
+
+    let (_, rest) = data.split_at_mut(start);
+
+    if rest.len() > sector_size {
+        Ok(rest.split_at_mut(sector_size).0)
+    } else {
+        Ok(rest)
+    }
+}
+
+const fn sectors(
+    ctx: &Aes128XTS,
+    data: &mut [u8],
+    sector_size: usize,
+    first_sector: u128,
+    decrypt: bool,
+) -> Result<(), Error> {
+
+    //
+    // check every sector length before processing any of them
+    //
+    let last = if sector_size == 0 { 0 } else { data.len() % sector_size };
+
+    if !data.is_empty() && (sector_size < 16 || (last > 0 && last < 16)) {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut rest = data;
+    let mut sector = first_sector;
+
+    while !rest.is_empty() {
+        let length = if rest.len() < sector_size { rest.len() } else { sector_size };
+        let (current, next) = rest.split_at_mut(length);
+
+        if let Err(err) = xts(&ctx.keys, &ctx.tweak_keys, sector, current, decrypt) {
+            return Err(err);
+        }
+
+        rest = next;
+        sector = sector.wrapping_add(1);
+    }
+
+    Ok(())
+}
+
+/// multiplication of the tweak by the primitive element alpha, little endian
+const fn multiply_alpha(tweak: &mut [u8; 16]) {
+    let mut carry = 0;
+
+    let mut i = 0;
+    while i < 16 {
+        let next = tweak[i] >> 7;
+        tweak[i] = (tweak[i] << 1) | carry;
+        carry = next;
+        i += 1;
+    }
+
+    tweak[0] ^= 0x87 & 0u8.wrapping_sub(carry);
+}
+
+/// C = E(P ^ T) ^ T, or the inverse when decrypting
+const fn process_block(
+    keys: &RoundKeys<11>,
+    tweak: &[u8; 16],
+    block: &[u8; 16],
+    decrypt: bool,
+) -> [u8; 16] {
+    let mut input = [0u8; 16];
+
+    let mut i = 0;
+    while i < 16 {
+        input[i] = block[i] ^ tweak[i];
+        i += 1;
+    }
+
+    let mut output = if decrypt {
+        equivalent_decrypt_block(&input, &keys.dec)
+    } else {
+        encrypt_block(&input, &keys.enc)
+    };
+
+    i = 0;
+    while i < 16 {
+        output[i] ^= tweak[i];
+        i += 1;
+    }
+
+    output
+}
+
+pub(crate) const fn xts(
+    keys: &RoundKeys<11>,
+    tweak_keys: &RoundKeys<11>,
+    sector: u128,
+    data: &mut [u8],
+    decrypt: bool,
+) -> Result<(), Error> {
+
+    //
+    // check sector length
+    //
+    if data.len() < 16 {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut tweak = encrypt_block(&sector.to_le_bytes(), &tweak_keys.enc);
+
+    //
+    // with a partial last block, the last full block is left to the stealing step
+    //
+    let partial = data.len() % 16;
+    let full = data.len() / 16 - if partial == 0 { 0 } else { 1 };
+
+    let mut i = 0;
+    while i < full {
+        let block = process_block(keys, &tweak, &read_block(data, i * 16), decrypt);
+        write_block(data, i * 16, &block);
+
+        multiply_alpha(&mut tweak);
+        i += 1;
+    }
+
+    if partial == 0 {
+        return Ok(());
+    }
+
+    //
+    // the last full block is processed with the tweak of the partial block when decrypting
+    //
+    let start = full * 16;
+    let mut next_tweak = tweak;
+    multiply_alpha(&mut next_tweak);
+
+    let (first_tweak, second_tweak) = if decrypt { (&next_tweak, &tweak) } else { (&tweak, &next_tweak) };
+
+    let mut stolen = process_block(keys, first_tweak, &read_block(data, start), decrypt);
+
+    //
+    // the partial block takes the head of that block and lends its bytes to it
+    //
+    let mut k = 0;
+    while k < partial {
+        let byte = data[start + 16 + k];
+        data[start + 16 + k] = stolen[k];
+        stolen[k] = byte;
+        k += 1;
+    }
+
+    let block = process_block(keys, second_tweak, &stolen, decrypt);
+    write_block(data, start, &block);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aes128XTS, Error};
+
+    const fn xts_key(data_key: [u8; 16], tweak_key: [u8; 16]) -> [u8; 32] {
+        let mut key = [0u8; 32];
+
+        let mut i = 0;
+        while i < 16 {
+            key[i] = data_key[i];
+            key[i + 16] = tweak_key[i];
+            i += 1;
+        }
+
+        key
+    }
+
+    #[test]
+    fn test_aes128_xts(){
+        //
+        // IEEE 1619 vectors 1 to 3
+        //
+        let mut data = [0u8; 32];
+        Aes128XTS::new([0u8; 32]).encrypt_sector(0, &mut data).unwrap();
+
+        assert_eq!(data, [
+            0x91, 0x7c, 0xf6, 0x9e, 0xbd, 0x68, 0xb2, 0xec,
+            0x9b, 0x9f, 0xe9, 0xa3, 0xea, 0xdd, 0xa6, 0x92,
+            0xcd, 0x43, 0xd2, 0xf5, 0x95, 0x98, 0xed, 0x85,
+            0x8c, 0x02, 0xc2, 0x65, 0x2f, 0xbf, 0x92, 0x2e
+        ]);
+
+        let ctx = Aes128XTS::new(xts_key([0x11; 16], [0x22; 16]));
+        let mut data = [0x44u8; 32];
+        ctx.encrypt_sector(0x3333333333, &mut data).unwrap();
+
+        assert_eq!(data, [
+            0xc4, 0x54, 0x18, 0x5e, 0x6a, 0x16, 0x93, 0x6e,
+            0x39, 0x33, 0x40, 0x38, 0xac, 0xef, 0x83, 0x8b,
+            0xfb, 0x18, 0x6f, 0xff, 0x74, 0x80, 0xad, 0xc4,
+            0x28, 0x93, 0x82, 0xec, 0xd6, 0xd3, 0x94, 0xf0
+        ]);
+
+        ctx.decrypt_sector(0x3333333333, &mut data).unwrap();
+        assert_eq!(data, [0x44u8; 32]);
+
+        const KEY_1: [u8; 16] = [
+            0xff, 0xfe, 0xfd, 0xfc, 0xfb, 0xfa, 0xf9, 0xf8,
+            0xf7, 0xf6, 0xf5, 0xf4, 0xf3, 0xf2, 0xf1, 0xf0
+        ];
+
+        let mut data = [0x44u8; 32];
+        Aes128XTS::new(xts_key(KEY_1, [0x22; 16])).encrypt_sector(0x3333333333, &mut data).unwrap();
+
+        assert_eq!(data, [
+            0xaf, 0x85, 0x33, 0x6b, 0x59, 0x7a, 0xfc, 0x1a,
+            0x90, 0x0b, 0x2e, 0xb2, 0x1e, 0xc9, 0x49, 0xd2,
+            0x92, 0xdf, 0x4c, 0x04, 0x7e, 0x0b, 0x21, 0x53,
+            0x21, 0x86, 0xa5, 0x97, 0x1a, 0x22, 0x7a, 0x89
+        ]);
+    }
+
+    #[test]
+    fn test_aes128_xts_ciphertext_stealing(){
+        //
+        // IEEE 1619 vectors 15 to 18
+        //
+        const KEY_1: [u8; 16] = [
+            0xff, 0xfe, 0xfd, 0xfc, 0xfb, 0xfa, 0xf9, 0xf8,
+            0xf7, 0xf6, 0xf5, 0xf4, 0xf3, 0xf2, 0xf1, 0xf0
+        ];
+
+        const KEY_2: [u8; 16] = [
+            0xbf, 0xbe, 0xbd, 0xbc, 0xbb, 0xba, 0xb9, 0xb8,
+            0xb7, 0xb6, 0xb5, 0xb4, 0xb3, 0xb2, 0xb1, 0xb0
+        ];
+
+        const SECTOR: u128 = 0x123456789a;
+
+        const EXPECTED: [[u8; 20]; 4] = [
+            [
+                0x6c, 0x16, 0x25, 0xdb, 0x46, 0x71, 0x52, 0x2d,
+                0x3d, 0x75, 0x99, 0x60, 0x1d, 0xe7, 0xca, 0x09,
+                0xed, 0, 0, 0
+            ],
+            [
+                0xd0, 0x69, 0x44, 0x4b, 0x7a, 0x7e, 0x0c, 0xab,
+                0x09, 0xe2, 0x44, 0x47, 0xd2, 0x4d, 0xeb, 0x1f,
+                0xed, 0xbf, 0, 0
+            ],
+            [
+                0xe5, 0xdf, 0x13, 0x51, 0xc0, 0x54, 0x4b, 0xa1,
+                0x35, 0x0b, 0x33, 0x63, 0xcd, 0x8e, 0xf4, 0xbe,
+                0xed, 0xbf, 0x9d, 0
+            ],
+            [
+                0x9d, 0x84, 0xc8, 0x13, 0xf7, 0x19, 0xaa, 0x2c,
+                0x7b, 0xe3, 0xf6, 0x61, 0x71, 0xc7, 0xc5, 0xc2,
+                0xed, 0xbf, 0x9d, 0xac
+            ],
+        ];
+
+        let ctx = Aes128XTS::new(xts_key(KEY_1, KEY_2));
+
+        for (i, expected) in EXPECTED.iter().enumerate() {
+            let length = 17 + i;
+            let plain: [u8; 20] = core::array::from_fn(|k| k as u8);
+
+            let mut data = plain;
+            ctx.encrypt_sector(SECTOR, &mut data[..length]).unwrap();
+
+            assert_eq!(data[..length], expected[..length]);
+
+            ctx.decrypt_sector(SECTOR, &mut data[..length]).unwrap();
+            assert_eq!(data, plain);
+        }
+    }
+
+    #[test]
+    fn test_aes128_xts_sectors(){
+        let ctx = Aes128XTS::new(xts_key([0x11; 16], [0x22; 16]));
+        let plain: [u8; 180] = core::array::from_fn(|k| k as u8);
+
+        //
+        // 3 sectors of 50 bytes followed by a shorter one of 30 bytes
+        //
+        let mut data = plain;
+        ctx.encrypt_sectors(&mut data, 50, 7).unwrap();
+
+        let mut sector = [0u8; 30];
+        sector.copy_from_slice(&plain[150..]);
+        ctx.encrypt_sector(10, &mut sector).unwrap();
+
+        assert_eq!(data[150..], sector);
+
+        ctx.decrypt_sectors(&mut data, 50, 7).unwrap();
+        assert_eq!(data, plain);
+
+        //
+        // a single sector can be processed in place within the buffer
+        //
+        ctx.encrypt_sectors(&mut data, 50, 0).unwrap();
+        ctx.decrypt_sector_at(&mut data, 50, 1).unwrap();
+
+        assert_eq!(data[50..100], plain[50..100]);
+        assert_ne!(data[..50], plain[..50]);
+
+        ctx.encrypt_sector_at(&mut data, 50, 1).unwrap();
+        ctx.decrypt_sectors(&mut data, 50, 0).unwrap();
+
+        assert_eq!(data, plain);
+    }
+
+    #[test]
+    fn test_aes128_xts_invalid_lengths(){
+        let ctx = Aes128XTS::new(xts_key([0x11; 16], [0x22; 16]));
+
+        let mut data = [0u8; 100];
+
+        assert_eq!(ctx.decrypt_sector(0, &mut data[..10]), Err(Error::InvalidLength));
+        assert_eq!(data, [0u8; 100]);
+
+        //
+        // a last sector shorter than a block is rejected before anything is processed
+        //
+        assert_eq!(ctx.encrypt_sectors(&mut data[..90], 40, 0), Err(Error::InvalidLength));
+        assert_eq!(ctx.decrypt_sectors(&mut data, 10, 0), Err(Error::InvalidLength));
+        assert_eq!(ctx.encrypt_sectors(&mut data, 0, 0), Err(Error::InvalidLength));
+        assert_eq!(data, [0u8; 100]);
+
+        assert_eq!(ctx.decrypt_sector_at(&mut data[..90], 40, 2), Err(Error::InvalidLength));
+
+        //
+        // sectors past the end of the buffer, or whose offset overflows
+        //
+        assert_eq!(ctx.encrypt_sector_at(&mut data, 50, 2), Err(Error::InvalidLength));
+        assert_eq!(ctx.encrypt_sector_at(&mut data, 50, usize::MAX as u128), Err(Error::InvalidLength));
+        assert_eq!(ctx.encrypt_sector_at(&mut data, 50, 1 << 64), Err(Error::InvalidLength));
+        assert_eq!(ctx.encrypt_sector_at(&mut data, 1, (1 << 64) + 1), Err(Error::InvalidLength));
+        assert_eq!(data, [0u8; 100]);
+
+        assert_eq!(ctx.encrypt_sectors(&mut [], 16, 0), Ok(()));
+    }
+}
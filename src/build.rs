@@ -0,0 +1,156 @@
+//!
+//! Helpers for build scripts generating the key and ivs used to encrypt at compile time.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("keys.rs");
+//!     const_aes::build::generate_key_module(out).unwrap();
+//! }
+//!
+//! // src/main.rs
+//! include!(concat!(env!("OUT_DIR"), "/keys.rs"));
+//! ```
+//!
+//! Keys are random unless the `CONST_AES_SEED` environment variable is set, in which case they
+//! are derived from its value so that builds are reproducible.
+//!
+//! Cargo only reruns the build script when `CONST_AES_SEED` changes, random keys are therefore
+//! kept until a clean build (`cargo clean`) or a change of the seed, not renewed on every build.
+//!
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::string::String;
+use std::vec;
+use std::vec::Vec;
+use std::println;
+
+use crate::Aes128CTR;
+use crate::utils::encrypt::encrypt_block;
+use crate::utils::key_schedule;
+
+/// environment variable holding the seed of reproducible builds
+pub const SEED_ENV: &str = "CONST_AES_SEED";
+
+/// number of ivs written by `generate_key_module`
+pub const DEFAULT_IV_COUNT: usize = 16;
+
+/// write a rust file declaring `KEY` and `DEFAULT_IV_COUNT` ivs in `IVS`
+pub fn generate_key_module<P: AsRef<Path>>(out_path: P) -> io::Result<()> {
+    generate_key_module_with_ivs(out_path, DEFAULT_IV_COUNT)
+}
+
+/// write a rust file declaring `pub const KEY: [u8; 16]` and `pub const IVS: [[u8; 16]; iv_count]`
+pub fn generate_key_module_with_ivs<P: AsRef<Path>>(out_path: P, iv_count: usize) -> io::Result<()> {
+
+    //
+    // only rerun the build script when the seed changes, unseeded keys stay the same until
+    // a clean build
+    //
+    println!("cargo:rerun-if-env-changed={}", SEED_ENV);
+
+    let seed = env::var(SEED_ENV).ok();
+    let bytes = key_material(seed.as_deref(), 16 * (iv_count + 1));
+
+    fs::write(out_path, key_module(&bytes, iv_count))
+}
+
+/// random bytes, or bytes derived from the seed when there is one
+fn key_material(seed: Option<&str>, length: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; length];
+
+    match seed {
+        Some(seed) => Aes128CTR::new(seed_key(seed.as_bytes()), [0u8; 16]).apply_keystream(&mut bytes),
+        None => const_aes_random::fill(&mut bytes),
+    }
+
+    bytes
+}
+
+///
+/// compress the seed into a 16 bytes key.
+///
+/// Each 16 bytes chunk of the seed, followed by its length, is used as the key of a
+/// Davies-Meyer compression so that different seeds give unrelated keys.
+///
+fn seed_key(seed: &[u8]) -> [u8; 16] {
+    let mut state = [0u8; 16];
+
+    let length = (seed.len() as u128).to_be_bytes();
+    let chunks = seed.chunks(16).chain(core::iter::once(&length[..]));
+
+    for chunk in chunks {
+        let mut key = [0u8; 16];
+        key[..chunk.len()].copy_from_slice(chunk);
+
+        let encrypted = encrypt_block(&state, &key_schedule(&key));
+
+        for i in 0..16 {
+            state[i] ^= encrypted[i];
+        }
+    }
+
+    state
+}
+
+fn key_module(bytes: &[u8], iv_count: usize) -> String {
+    let mut module = String::from("// generated by const_aes::build, do not edit\n\n");
+
+    let _ = writeln!(module, "pub const KEY: [u8; 16] = {};\n", array(&bytes[..16]));
+    let _ = writeln!(module, "pub const IVS: [[u8; 16]; {}] = [", iv_count);
+
+    for iv in bytes[16..].chunks(16) {
+        let _ = writeln!(module, "    {},", array(iv));
+    }
+
+    module.push_str("];\n");
+    module
+}
+
+fn array(bytes: &[u8]) -> String {
+    let bytes: Vec<String> = bytes.iter().map(|byte| std::format!("0x{:02x}", byte)).collect();
+    std::format!("[{}]", bytes.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_material_seeded() {
+        let first = key_material(Some("release-1.0"), 48);
+
+        assert_eq!(first, key_material(Some("release-1.0"), 48));
+        assert_ne!(first, key_material(Some("release-1.1"), 48));
+        assert_ne!(key_material(None, 48), key_material(None, 48));
+    }
+
+    #[test]
+    fn test_key_module() {
+        let bytes: Vec<u8> = (0..48).collect();
+        let module = key_module(&bytes, 2);
+
+        assert!(module.contains("pub const KEY: [u8; 16] = [0x00, 0x01, 0x02,"));
+        assert!(module.contains("pub const IVS: [[u8; 16]; 2] = [\n    [0x10, 0x11,"));
+        assert!(module.contains("    [0x20, 0x21,"));
+    }
+
+    #[test]
+    fn test_generate_key_module() {
+        //
+        // unique per process so that parallel test runs do not share the file
+        //
+        let path = env::temp_dir().join(std::format!("const_aes_build_test_keys_{}.rs", std::process::id()));
+
+        generate_key_module_with_ivs(&path, 3).unwrap();
+        let module = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(module.contains("pub const KEY: [u8; 16]"));
+        assert_eq!(module.matches("    [0x").count(), 3);
+    }
+}
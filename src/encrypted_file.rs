@@ -0,0 +1,40 @@
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use crate::Aes128CBC;
+
+///
+/// file encrypted at compile time by `include_encrypted!`.
+///
+/// Only the cipher is stored in the binary, the plain bytes are recovered by `decrypt`.
+///
+pub struct EncryptedFile {
+    ctx: Aes128CBC,
+    cipher: &'static [u8],
+}
+
+impl EncryptedFile {
+    pub const fn new(ctx: Aes128CBC, cipher: &'static [u8]) -> Self {
+        Self {
+            ctx,
+            cipher,
+        }
+    }
+
+    /// length of the cipher, the plain file is up to 16 bytes shorter
+    pub const fn cipher_len(&self) -> usize {
+        self.cipher.len()
+    }
+
+    /// decrypt the file at runtime
+    pub fn decrypt(&self) -> Vec<u8> {
+
+        //
+        // hide the cipher from the optimizer so the decryption is not folded
+        //
+        match self.ctx.decrypt_slice(black_box(self.cipher)) {
+            Ok(plain) => plain,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use core::fmt;
+
+/// errors returned by the fallible decryption functions
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// the cipher (or tag) length is not valid for the mode
+    InvalidLength,
+    /// the padding byte is 0, greater than the block size or than the data
+    InvalidPadding,
+    /// the padding bytes do not all match the padding byte
+    ///
+    /// PKCS#7 never returns it and reports every failure as `InvalidPadding` instead.
+    InconsistentPadding,
+    /// the authentication tag does not match the data
+    AuthenticationFailed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength => f.write_str("invalid cipher length"),
+            Error::InvalidPadding => f.write_str("invalid padding byte"),
+            Error::InconsistentPadding => f.write_str("inconsistent padding bytes"),
+            Error::AuthenticationFailed => f.write_str("authentication failed"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
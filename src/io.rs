@@ -0,0 +1,419 @@
+//!
+//! `std::io` adapters encrypting or decrypting with CBC on the fly, and `Read` + `Seek` for
+//! `CtrReader`.
+//!
+//! ```ignore
+//! let ctx = Aes128CBC::new(KEY, IV);
+//!
+//! let mut writer = EncryptingWriter::new(File::create("data.enc")?, ctx.encryptor());
+//! writer.write_all(b"secret")?;
+//! writer.finish()?;
+//!
+//! let mut reader = DecryptingReader::new(File::open("data.enc")?, ctx.decryptor());
+//! reader.read_to_end(&mut plain)?;
+//! ```
+//!
+//! Padding and length errors are returned as `io::ErrorKind::InvalidData` errors.
+//!
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::{CbcDecryptor, CbcEncryptor, CtrReader, DecryptingReader, EncryptingWriter};
+use crate::padding::Padding;
+
+/// number of bytes processed by the adapters at once
+pub(crate) const CHUNK_SIZE: usize = 1024;
+
+impl<W: Write, const ROUND_KEYS: usize, P: Padding> EncryptingWriter<W, ROUND_KEYS, P> {
+    pub fn new(inner: W, encryptor: CbcEncryptor<ROUND_KEYS, P>) -> Self {
+        Self {
+            inner,
+            encryptor,
+            cipher: [0u8; CHUNK_SIZE],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// write the last padded block, flush and return the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.drain()?;
+
+        let Self { mut inner, encryptor, .. } = self;
+
+        let mut cipher = [0u8; 16];
+        let written = encryptor.finalize(&mut cipher)?;
+
+        inner.write_all(&cipher[..written])?;
+        inner.flush()?;
+
+        Ok(inner)
+    }
+
+    ///
+    /// write the pending cipher to the inner writer.
+    ///
+    /// The cipher is only dropped once written, so that a failing inner writer can be retried
+    /// without losing blocks the encryptor already chained.
+    ///
+    fn drain(&mut self) -> io::Result<()> {
+        while self.start < self.end {
+            match self.inner.write(&self.cipher[self.start..self.end]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(written) => self.start += written,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write, const ROUND_KEYS: usize, P: Padding> Write for EncryptingWriter<W, ROUND_KEYS, P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+
+        //
+        // new input is only accepted once the cipher of the previous one was written, at most
+        // 15 bytes may be buffered by the encryptor so the cipher always fits
+        //
+        self.drain()?;
+
+        let input = &buf[..buf.len().min(CHUNK_SIZE - 16)];
+
+        self.end = self.encryptor.update(input, &mut self.cipher)?;
+        self.start = 0;
+
+        Ok(input.len())
+    }
+
+    /// flush the inner writer, the bytes of an incomplete block stay buffered until `finish`
+    fn flush(&mut self) -> io::Result<()> {
+        self.drain()?;
+        self.inner.flush()
+    }
+}
+
+impl<R: Read, const ROUND_KEYS: usize, P: Padding> DecryptingReader<R, ROUND_KEYS, P> {
+    pub fn new(inner: R, decryptor: CbcDecryptor<ROUND_KEYS, P>) -> Self {
+        Self {
+            inner,
+            decryptor: Some(decryptor),
+            error: None,
+            plain: [0u8; CHUNK_SIZE],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    ///
+    /// decrypt the next chunk of the inner reader, returns false once everything was decrypted.
+    ///
+    /// Once decryption failed the same error is returned by every call, so that a truncated or
+    /// tampered stream never ends like a valid one.
+    ///
+    fn fill(&mut self) -> io::Result<bool> {
+        if let Some(err) = self.error {
+            return Err(err.into());
+        }
+
+        let Some(mut decryptor) = self.decryptor.take() else {
+            return Ok(false);
+        };
+
+        let mut cipher = [0u8; CHUNK_SIZE];
+
+        let read = match self.inner.read(&mut cipher) {
+            Ok(read) => read,
+            Err(err) => {
+                self.decryptor = Some(decryptor);
+                return Err(err);
+            }
+        };
+
+        //
+        // the padding is removed once the inner reader reaches its end
+        //
+        let written = match read {
+            0 => decryptor.finalize(&mut self.plain),
+            _ => {
+                let written = decryptor.update(&cipher[..read], &mut self.plain);
+                self.decryptor = Some(decryptor);
+
+                written
+            }
+        };
+
+        match written {
+            Ok(written) => {
+                self.start = 0;
+                self.end = written;
+
+                Ok(true)
+            }
+            Err(err) => {
+                self.error = Some(err);
+                Err(err.into())
+            }
+        }
+    }
+}
+
+impl<R: Read, const ROUND_KEYS: usize, P: Padding> Read for DecryptingReader<R, ROUND_KEYS, P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+
+        //
+        // short reads of the inner reader may not complete a block, keep reading until
+        // some plain text is available
+        //
+        while self.start == self.end {
+            if buf.is_empty() || !self.fill()? {
+                return Ok(0);
+            }
+        }
+
+        let length = buf.len().min(self.end - self.start);
+        buf[..length].copy_from_slice(&self.plain[self.start..self.start + length]);
+        self.start += length;
+
+        Ok(length)
+    }
+}
+
+impl Read for CtrReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.read_at(self.position, buf);
+        self.position += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl Seek for CtrReader<'_> {
+    /// seeking past the end is allowed, reads then return 0 bytes
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::End(offset) => (self.len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        let Some(position) = position else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position"));
+        };
+
+        self.position = position;
+
+        Ok(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read, Seek, SeekFrom, Write};
+    use std::thread;
+    use std::vec::Vec;
+
+    use crate::{Aes128CBC, Aes128CTR, Aes256CBC, CtrReader, DecryptingReader, EncryptingWriter};
+    use crate::padding::NoPadding;
+
+    const KEY: [u8; 16] = [0x2b; 16];
+    const IV: [u8; 16] = [0x0f; 16];
+
+    /// reader returning at most a few bytes per call
+    struct ShortReader<'a> {
+        data: &'a [u8],
+        max: usize,
+    }
+
+    impl Read for ShortReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let length = buf.len().min(self.max).min(self.data.len());
+            buf[..length].copy_from_slice(&self.data[..length]);
+            self.data = &self.data[length..];
+
+            Ok(length)
+        }
+    }
+
+    /// writer failing once with `WouldBlock` on its first write
+    struct FailingWriter {
+        data: Vec<u8>,
+        failed: bool,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if !self.failed {
+                self.failed = true;
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+
+            self.data.extend_from_slice(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn plain(length: usize) -> Vec<u8> {
+        (0..length).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn test_encrypting_writer(){
+        let ctx = Aes128CBC::new(KEY, IV);
+
+        for length in [0, 15, 16, 3000] {
+            let plain = plain(length);
+
+            let mut writer = EncryptingWriter::new(Vec::new(), ctx.encryptor());
+
+            for part in plain.chunks(7) {
+                writer.write_all(part).unwrap();
+            }
+
+            assert_eq!(writer.finish().unwrap(), ctx.encrypt_vec(&plain));
+        }
+    }
+
+    #[test]
+    fn test_encrypting_writer_retry(){
+        let ctx = Aes128CBC::new(KEY, IV);
+        let mut writer = EncryptingWriter::new(FailingWriter { data: Vec::new(), failed: false }, ctx.encryptor());
+
+        writer.write_all(&[b'A'; 16]).unwrap();
+
+        //
+        // the failed write is retried without consuming the input
+        //
+        assert_eq!(writer.write_all(&[b'B'; 16]).unwrap_err().kind(), io::ErrorKind::WouldBlock);
+        writer.write_all(&[b'B'; 16]).unwrap();
+
+        let cipher = writer.finish().unwrap().data;
+
+        assert_eq!(ctx.decrypt_slice(&cipher).unwrap(), [[b'A'; 16], [b'B'; 16]].concat());
+    }
+
+    #[test]
+    fn test_decrypting_reader(){
+        let ctx = Aes256CBC::new([0x60; 32], IV);
+
+        for length in [0, 15, 16, 3000] {
+            let plain = plain(length);
+            let cipher = ctx.encrypt_vec(&plain);
+
+            for max in [1, 5, 16, 4096] {
+                let mut reader = DecryptingReader::new(ShortReader { data: &cipher, max }, ctx.decryptor());
+                let mut decrypted = Vec::new();
+
+                reader.read_to_end(&mut decrypted).unwrap();
+
+                assert_eq!(decrypted, plain);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decrypting_reader_errors(){
+        let ctx = Aes128CBC::new(KEY, IV);
+
+        //
+        // a truncated cipher
+        //
+        let cipher = ctx.encrypt_vec(&plain(40));
+        let mut reader = DecryptingReader::new(&cipher[..40], ctx.decryptor());
+
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        //
+        // retrying keeps failing instead of reporting the end of the stream
+        //
+        assert_eq!(reader.read(&mut [0u8; 16]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(reader.read(&mut [0u8; 16]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        //
+        // a cipher without padding
+        //
+        let cipher = Aes128CBC::with_padding(KEY, IV, NoPadding).encrypt_vec(&[0u8; 32]);
+        let mut reader = DecryptingReader::new(cipher.as_slice(), ctx.decryptor());
+
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_ctr_reader_seek(){
+        let plain = plain(1000);
+        let mut cipher = plain.clone();
+        Aes128CTR::new(KEY, IV).apply_keystream(&mut cipher);
+
+        let mut reader = CtrReader::new(Aes128CTR::new(KEY, IV), &cipher);
+        let mut buf = [0u8; 40];
+
+        assert_eq!(reader.seek(SeekFrom::Start(100)).unwrap(), 100);
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf[..], plain[100..140]);
+
+        assert_eq!(reader.seek(SeekFrom::Current(-33)).unwrap(), 107);
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf[..], plain[107..147]);
+
+        assert_eq!(reader.seek(SeekFrom::End(-7)).unwrap(), 993);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, plain[993..]);
+
+        assert_eq!(reader.seek(SeekFrom::End(10)).unwrap(), 1010);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+        assert_eq!(reader.seek(SeekFrom::Current(-2000)).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_ctr_reader_threads(){
+        let plain = plain(4096);
+        let mut cipher = plain.clone();
+        Aes128CTR::new(KEY, IV).apply_keystream(&mut cipher);
+
+        let reader = CtrReader::new(Aes128CTR::new(KEY, IV), &cipher);
+
+        thread::scope(|scope| {
+            for part in 0..8 {
+                let (reader, plain) = (&reader, &plain);
+
+                scope.spawn(move || {
+                    let offset = part * 509;
+                    let mut buf = [0u8; 509];
+
+                    let read = reader.read_at(offset as u64, &mut buf);
+                    assert_eq!(buf[..read], plain[offset..offset + read]);
+                });
+            }
+        });
+    }
+}
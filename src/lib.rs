@@ -1,11 +1,238 @@
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod utils;
 pub mod aes;
+pub mod padding;
+#[cfg(feature = "build")]
+pub mod build;
+#[cfg(feature = "std")]
+pub mod io;
+mod error;
+mod plain_buf;
+#[cfg(feature = "macros")]
+mod encrypted_file;
+
+pub use error::Error;
+pub use plain_buf::PlainBuf;
+#[cfg(feature = "macros")]
+pub use encrypted_file::EncryptedFile;
+
+use core::marker::PhantomData;
+use padding::Pkcs7;
+
+#[cfg(feature = "macros")]
+pub use const_aes_macros::{encrypt_bytes, encrypt_str, include_encrypted};
+
+/// items used by the code generated by the macros, not part of the public api
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
+    pub use core::hint::black_box;
+}
+
+/// expanded key schedules, computed once and shared by every mode
+///
+/// `ROUND_KEYS` is 11, 13 or 15 for AES 128, 192 and 256.
+#[derive(Clone, Copy)]
+pub struct RoundKeys<const ROUND_KEYS: usize> {
+    enc: [[u8; 16]; ROUND_KEYS],
+    dec: [[u8; 16]; ROUND_KEYS],
+}
+
+pub struct Aes128ECB<P = Pkcs7> {
+    keys: RoundKeys<11>,
+    padding: PhantomData<P>,
+}
+
+pub struct Aes192ECB<P = Pkcs7> {
+    keys: RoundKeys<13>,
+    padding: PhantomData<P>,
+}
+
+pub struct Aes256ECB<P = Pkcs7> {
+    keys: RoundKeys<15>,
+    padding: PhantomData<P>,
+}
+
+pub struct Aes128CBC<P = Pkcs7> {
+    keys: RoundKeys<11>,
+    iv: [u8; 16],
+    padding: PhantomData<P>,
+}
 
-pub struct Aes128ECB {
-    key: [u8; 16],
+pub struct Aes192CBC<P = Pkcs7> {
+    keys: RoundKeys<13>,
+    iv: [u8; 16],
+    padding: PhantomData<P>,
 }
 
-pub struct Aes128CBC {
-    key: [u8; 16],
+pub struct Aes256CBC<P = Pkcs7> {
+    keys: RoundKeys<15>,
     iv: [u8; 16],
-}
\ No newline at end of file
+    padding: PhantomData<P>,
+}
+
+/// incremental ECB encryption, created by the `encryptor` method of the ECB contexts
+pub struct EcbEncryptor<const ROUND_KEYS: usize, P = Pkcs7> {
+    keys: RoundKeys<ROUND_KEYS>,
+    buffer: [u8; 16],
+    buffered: usize,
+    padding: PhantomData<P>,
+}
+
+/// incremental ECB decryption, the last block is held back until `finalize` removes the padding
+pub struct EcbDecryptor<const ROUND_KEYS: usize, P = Pkcs7> {
+    keys: RoundKeys<ROUND_KEYS>,
+    buffer: [u8; 16],
+    buffered: usize,
+    padding: PhantomData<P>,
+}
+
+/// incremental CBC encryption, created by the `encryptor` method of the CBC contexts
+pub struct CbcEncryptor<const ROUND_KEYS: usize, P = Pkcs7> {
+    keys: RoundKeys<ROUND_KEYS>,
+    prev: [u8; 16],
+    buffer: [u8; 16],
+    buffered: usize,
+    padding: PhantomData<P>,
+}
+
+/// incremental CBC decryption, the last block is held back until `finalize` removes the padding
+pub struct CbcDecryptor<const ROUND_KEYS: usize, P = Pkcs7> {
+    keys: RoundKeys<ROUND_KEYS>,
+    prev: [u8; 16],
+    buffer: [u8; 16],
+    buffered: usize,
+    padding: PhantomData<P>,
+}
+
+/// `Write` adapter encrypting with CBC everything written to the inner writer
+///
+/// `finish` must be called to write the last padded block.
+#[cfg(feature = "std")]
+pub struct EncryptingWriter<W, const ROUND_KEYS: usize, P = Pkcs7> {
+    inner: W,
+    encryptor: CbcEncryptor<ROUND_KEYS, P>,
+    cipher: [u8; io::CHUNK_SIZE],
+    start: usize,
+    end: usize,
+}
+
+/// `Read` adapter decrypting with CBC everything read from the inner reader
+#[cfg(feature = "std")]
+pub struct DecryptingReader<R, const ROUND_KEYS: usize, P = Pkcs7> {
+    inner: R,
+    decryptor: Option<CbcDecryptor<ROUND_KEYS, P>>,
+    error: Option<Error>,
+    plain: [u8; io::CHUNK_SIZE],
+    start: usize,
+    end: usize,
+}
+
+/// order of the last two blocks of CBC with ciphertext stealing (NIST SP 800-38A addendum)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CtsVariant {
+    /// the truncated next to last block comes before the last block
+    Cs1,
+    /// the last two blocks are swapped unless the data is block aligned
+    Cs2,
+    /// the last two blocks are always swapped, as in Kerberos (RFC 3962)
+    Cs3,
+}
+
+/// how the 16 bytes counter block of the CTR mode is incremented
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CounterLayout {
+    /// the whole block is a 128 bit big endian counter
+    Counter128,
+    /// 96 bit nonce followed by a 32 bit big endian counter, the nonce is never modified
+    Nonce96Counter32,
+}
+
+/// key wrap (RFC 3394) and key wrap with padding (RFC 5649)
+pub struct Aes128KW {
+    keys: RoundKeys<11>,
+}
+
+pub struct Aes192KW {
+    keys: RoundKeys<13>,
+}
+
+pub struct Aes256KW {
+    keys: RoundKeys<15>,
+}
+
+pub struct Aes128OFB {
+    keys: RoundKeys<11>,
+    iv: [u8; 16],
+}
+
+/// CFB with segments of `SEGMENT_BITS` bits, either 1, 8 or 128
+pub struct Aes128CFB<const SEGMENT_BITS: usize> {
+    keys: RoundKeys<11>,
+    iv: [u8; 16],
+}
+
+pub struct Aes128CTR {
+    keys: RoundKeys<11>,
+    counter: [u8; 16],
+    layout: CounterLayout,
+}
+
+/// random access decryption of a CTR cipher, with `Read` and `Seek` under the `std` feature
+pub struct CtrReader<'a> {
+    ctx: Aes128CTR,
+    cipher: &'a [u8],
+    #[cfg(feature = "std")]
+    position: u64,
+}
+
+pub struct Aes128GCM {
+    keys: RoundKeys<11>,
+    h: u128,
+}
+
+/// CCM with tags of `TAG_LENGTH` bytes and nonces of `NONCE_LENGTH` bytes, a tag length of 0 gives CCM*
+pub struct Aes128CCM<const TAG_LENGTH: usize, const NONCE_LENGTH: usize> {
+    keys: RoundKeys<11>,
+}
+
+pub struct Aes128CMAC {
+    keys: RoundKeys<11>,
+    k1: [u8; 16],
+    k2: [u8; 16],
+}
+
+/// AES-CMAC-SIV with a 256 bit key, the first half authenticates and the second encrypts
+pub struct Aes128SIV {
+    mac: Aes128CMAC,
+    keys: RoundKeys<11>,
+}
+
+/// AES-128-GCM-SIV, the key generating key derives new keys for each nonce
+pub struct Aes128GCMSIV {
+    keys: RoundKeys<11>,
+}
+
+/// XTS-AES-128 (IEEE 1619), the first half of the 256 bit key encrypts the data and the second the tweaks
+pub struct Aes128XTS {
+    keys: RoundKeys<11>,
+    tweak_keys: RoundKeys<11>,
+}
+
+/// incremental CMAC computation, created by `Aes128CMAC::state`
+#[derive(Clone, Copy)]
+pub struct CmacState<'a> {
+    cmac: &'a Aes128CMAC,
+    state: [u8; 16],
+    buffer: [u8; 16],
+    buffered: usize,
+}
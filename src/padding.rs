@@ -0,0 +1,240 @@
+//!
+//! Padding schemes of the block modes.
+//!
+//! Const functions cannot call trait methods, so each scheme is a marker type whose
+//! `Padding::SCHEME` constant selects the const implementation in `PaddingScheme`.
+//!
+
+use crate::Error;
+use crate::utils::decrypt::pkcs7_unpadded_length;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PaddingScheme {
+    /// every padding byte holds the padding length
+    Pkcs7,
+    /// 0x80 followed by zeros (ISO/IEC 7816-4)
+    Iso7816,
+    /// zeros followed by the padding length (ANSI X9.23)
+    AnsiX923,
+    /// random bytes followed by the padding length (ISO 10126)
+    Iso10126,
+    /// zeros up to the next block boundary, nothing is added to aligned data
+    Zero,
+    /// no padding, the data must be block aligned
+    None,
+}
+
+/// padding used by a block mode context, implemented by the marker types of this module
+pub trait Padding: Copy {
+    const SCHEME: PaddingScheme;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pkcs7;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Iso7816;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnsiX923;
+
+/// const functions have no access to randomness, the filler bytes are zeros when
+/// padding and ignored when unpadding.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Iso10126;
+
+/// trailing zeros of the data in its last block cannot be told apart from the padding and are
+/// removed as well, the zeros of the blocks before it are kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ZeroPadding;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoPadding;
+
+impl Padding for Pkcs7 {
+    const SCHEME: PaddingScheme = PaddingScheme::Pkcs7;
+}
+
+impl Padding for Iso7816 {
+    const SCHEME: PaddingScheme = PaddingScheme::Iso7816;
+}
+
+impl Padding for AnsiX923 {
+    const SCHEME: PaddingScheme = PaddingScheme::AnsiX923;
+}
+
+impl Padding for Iso10126 {
+    const SCHEME: PaddingScheme = PaddingScheme::Iso10126;
+}
+
+impl Padding for ZeroPadding {
+    const SCHEME: PaddingScheme = PaddingScheme::Zero;
+}
+
+impl Padding for NoPadding {
+    const SCHEME: PaddingScheme = PaddingScheme::None;
+}
+
+impl PaddingScheme {
+
+    /// length of data of the given length once padded, unaligned data keeps its length without padding
+    pub const fn padded_length(self, length: usize) -> usize {
+        match self {
+            PaddingScheme::Zero => length.next_multiple_of(16),
+            PaddingScheme::None => length,
+            _ => length + (16 - length % 16),
+        }
+    }
+
+    /// pad the first `length` bytes of buf up to the end of buf
+    pub const fn pad(self, buf: &mut [u8], length: usize) {
+        let padding = buf.len() - length;
+
+        let mut i = length;
+        while i < buf.len() {
+            buf[i] = match self {
+                PaddingScheme::Pkcs7 => padding as u8,
+                PaddingScheme::Iso7816 if i == length => 0x80,
+                PaddingScheme::AnsiX923 | PaddingScheme::Iso10126 if i == buf.len() - 1 => padding as u8,
+                _ => 0,
+            };
+
+            i += 1;
+        }
+    }
+
+    /// length of the data once the padding is removed
+    pub const fn unpadded_length(self, buf: &[u8]) -> Result<usize, Error> {
+        match self {
+            PaddingScheme::Pkcs7 => pkcs7_unpadded_length(buf),
+            PaddingScheme::Iso7816 => iso7816_unpadded_length(buf),
+            PaddingScheme::AnsiX923 => length_byte_unpadded_length(buf, true),
+            PaddingScheme::Iso10126 => length_byte_unpadded_length(buf, false),
+            PaddingScheme::Zero => {
+
+                //
+                // only the zeros of the last block are removed, like the streaming decryptor
+                // which never sees the blocks before it
+                //
+                let mut length = buf.len();
+                let last = buf.len().saturating_sub(16);
+
+                while length > last && buf[length - 1] == 0 {
+                    length -= 1;
+                }
+
+                Ok(length)
+            }
+            PaddingScheme::None => Ok(buf.len()),
+        }
+    }
+}
+
+const fn iso7816_unpadded_length(buf: &[u8]) -> Result<usize, Error> {
+    if buf.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+
+    //
+    // skip the zeros of the last block looking for the 0x80 marker
+    //
+    let mut i = buf.len() - 1;
+    let last = buf.len().saturating_sub(16);
+
+    loop {
+        match buf[i] {
+            0x80 => return Ok(i),
+            0x00 if i > last => i -= 1,
+            0x00 => return Err(Error::InvalidPadding),
+            _ => return Err(Error::InconsistentPadding),
+        }
+    }
+}
+
+/// ANSI X9.23 and ISO 10126 both end with the padding length, only the former has zero fillers
+const fn length_byte_unpadded_length(buf: &[u8], zero_filled: bool) -> Result<usize, Error> {
+    if buf.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+
+    let padding = buf[buf.len() - 1] as usize;
+
+    if padding == 0 || padding > 16 || padding > buf.len() {
+        return Err(Error::InvalidPadding);
+    }
+
+    if zero_filled {
+        let mut i = buf.len() - padding;
+        while i < buf.len() - 1 {
+            if buf[i] != 0 {
+                return Err(Error::InconsistentPadding);
+            }
+
+            i += 1;
+        }
+    }
+
+    Ok(buf.len() - padding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn padded<const N: usize>(scheme: PaddingScheme, data: &[u8]) -> [u8; N] {
+        let mut buf = [0xAAu8; N];
+
+        let mut i = 0;
+        while i < data.len() {
+            buf[i] = data[i];
+            i += 1;
+        }
+
+        scheme.pad(&mut buf, data.len());
+        buf
+    }
+
+    #[test]
+    fn test_padded_length() {
+        assert_eq!(PaddingScheme::Pkcs7.padded_length(15), 16);
+        assert_eq!(PaddingScheme::Iso7816.padded_length(16), 32);
+        assert_eq!(PaddingScheme::AnsiX923.padded_length(0), 16);
+        assert_eq!(PaddingScheme::Zero.padded_length(17), 32);
+        assert_eq!(PaddingScheme::Zero.padded_length(32), 32);
+        assert_eq!(PaddingScheme::None.padded_length(32), 32);
+        assert_eq!(PaddingScheme::None.padded_length(5), 5);
+    }
+
+    #[test]
+    fn test_pad() {
+        const DATA: [u8; 13] = *b"hello, world!";
+
+        assert_eq!(padded::<16>(PaddingScheme::Pkcs7, &DATA)[13..], [3, 3, 3]);
+        assert_eq!(padded::<16>(PaddingScheme::Iso7816, &DATA)[13..], [0x80, 0, 0]);
+        assert_eq!(padded::<16>(PaddingScheme::AnsiX923, &DATA)[13..], [0, 0, 3]);
+        assert_eq!(padded::<16>(PaddingScheme::Iso10126, &DATA)[15], 3);
+        assert_eq!(padded::<16>(PaddingScheme::Zero, &DATA)[13..], [0, 0, 0]);
+        assert_eq!(padded::<32>(PaddingScheme::Iso7816, &[0u8; 16])[16..], [
+            0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+        ]);
+    }
+
+    #[test]
+    fn test_unpadded_length() {
+        assert_eq!(PaddingScheme::Iso7816.unpadded_length(&[1, 2, 0x80, 0, 0]), Ok(2));
+        assert_eq!(PaddingScheme::Iso7816.unpadded_length(&[0x80; 16]), Ok(15));
+        assert_eq!(PaddingScheme::Iso7816.unpadded_length(&[0u8; 32]), Err(Error::InvalidPadding));
+        assert_eq!(PaddingScheme::Iso7816.unpadded_length(&[1, 2, 3]), Err(Error::InconsistentPadding));
+
+        assert_eq!(PaddingScheme::AnsiX923.unpadded_length(&[1, 0, 0, 3]), Ok(1));
+        assert_eq!(PaddingScheme::AnsiX923.unpadded_length(&[1, 7, 0, 3]), Err(Error::InconsistentPadding));
+        assert_eq!(PaddingScheme::AnsiX923.unpadded_length(&[1, 0, 0, 0]), Err(Error::InvalidPadding));
+
+        assert_eq!(PaddingScheme::Iso10126.unpadded_length(&[1, 7, 9, 3]), Ok(1));
+        assert_eq!(PaddingScheme::Iso10126.unpadded_length(&[1, 7, 9, 17]), Err(Error::InvalidPadding));
+
+        assert_eq!(PaddingScheme::Zero.unpadded_length(&[1, 2, 0, 0]), Ok(2));
+        assert_eq!(PaddingScheme::Zero.unpadded_length(&[0u8; 20]), Ok(4));
+        assert_eq!(PaddingScheme::None.unpadded_length(&[1, 2, 0, 0]), Ok(4));
+    }
+}
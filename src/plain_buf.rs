@@ -0,0 +1,40 @@
+/// fixed size buffer returned by the allocation free decryption functions
+///
+/// Only the first `len()` bytes hold the plain data, the rest is what remains of the padding.
+#[derive(Clone, Copy, Debug)]
+pub struct PlainBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> PlainBuf<N> {
+    pub(crate) const fn new(buf: [u8; N], len: usize) -> Self {
+        Self {
+            buf,
+            len,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn as_slice(&self) -> &[u8] {
+        self.buf.split_at(self.len).0
+    }
+
+    /// the whole buffer along with the length of the plain data
+    pub const fn into_inner(self) -> ([u8; N], usize) {
+        (self.buf, self.len)
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for PlainBuf<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
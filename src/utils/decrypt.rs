@@ -1,4 +1,8 @@
-use crate::utils::{add_round_key, gf_multiply, SBOX};
+use crate::Error;
+use crate::utils::{add_round_key, gf_multiply_table, SBOX};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 pub const fn sub_bytes_rev(block: &mut [u8; 16], rsbox: &[u8; 256]) {
     let mut i: u8 = 0;
@@ -9,6 +13,15 @@ pub const fn sub_bytes_rev(block: &mut [u8; 16], rsbox: &[u8; 256]) {
     }
 }
 
+/// inverse sbox, searched once at compile time
+pub const RSBOX: [u8; 256] = calculate_rsbox();
+
+/// multiplication tables used by the inverse MixColumns so that it only does lookups
+const MUL_9: [u8; 256] = gf_multiply_table(0x09);
+const MUL_11: [u8; 256] = gf_multiply_table(0x0B);
+const MUL_13: [u8; 256] = gf_multiply_table(0x0D);
+const MUL_14: [u8; 256] = gf_multiply_table(0x0E);
+
 /// in order to make the lib as tiny as possible the RSBOX is calculated from the SBOX
 /// However is that function not more than 256 bytes?
 pub const fn calculate_rsbox() -> [u8; 256] {
@@ -71,60 +84,122 @@ pub const fn mix_columns_rev(block: &mut [u8; 16]) {
 
         // Inverse matrix multiplication for each column
         // Uses different coefficients: 0x0E, 0x0B, 0x0D, 0x09
-        block[i] = gf_multiply(0x0E, temp[i]) ^
-            gf_multiply(0x0B, temp[i + 1]) ^
-            gf_multiply(0x0D, temp[i + 2]) ^
-            gf_multiply(0x09, temp[i + 3]);
-
-        block[i + 1] = gf_multiply(0x09, temp[i]) ^
-            gf_multiply(0x0E, temp[i + 1]) ^
-            gf_multiply(0x0B, temp[i + 2]) ^
-            gf_multiply(0x0D, temp[i + 3]);
-
-        block[i + 2] = gf_multiply(0x0D, temp[i]) ^
-            gf_multiply(0x09, temp[i + 1]) ^
-            gf_multiply(0x0E, temp[i + 2]) ^
-            gf_multiply(0x0B, temp[i + 3]);
-
-        block[i + 3] = gf_multiply(0x0B, temp[i]) ^
-            gf_multiply(0x0D, temp[i + 1]) ^
-            gf_multiply(0x09, temp[i + 2]) ^
-            gf_multiply(0x0E, temp[i + 3]);
+        let a = temp[i] as usize;
+        let b = temp[i + 1] as usize;
+        let c = temp[i + 2] as usize;
+        let d = temp[i + 3] as usize;
+
+        block[i] = MUL_14[a] ^ MUL_11[b] ^ MUL_13[c] ^ MUL_9[d];
+        block[i + 1] = MUL_9[a] ^ MUL_14[b] ^ MUL_11[c] ^ MUL_13[d];
+        block[i + 2] = MUL_13[a] ^ MUL_9[b] ^ MUL_14[c] ^ MUL_11[d];
+        block[i + 3] = MUL_11[a] ^ MUL_13[b] ^ MUL_9[c] ^ MUL_14[d];
 
         col += 1;
     }
 }
 
-pub fn remove_pkcs7_padding<const CIPHER_LENGTH: usize>(plain: &[u8; CIPHER_LENGTH]) -> Vec<u8> {
+/// 0xFF if a < b else 0x00, without branching (both values must be below 2^63)
+const fn ct_lt_mask(a: usize, b: usize) -> u8 {
+    ((a.wrapping_sub(b) >> (usize::BITS - 1)) as u8).wrapping_neg()
+}
+
+///
+/// check the pkcs7 padding in constant time, returns 0x00 if it is valid and 0xFF otherwise.
+///
+/// The last 16 bytes (or the whole data if shorter) are always read and the padding value is
+/// only ever used through masks, so the time taken does not depend on the padding bytes.
+///
+pub const fn pkcs7_padding_mask(plain: &[u8]) -> u8 {
+    let length = plain.len();
+    let padding = plain[length - 1] as usize;
+
+    //
+    // padding must be in 1..=16 and fit in the data
+    //
+    let mut bad = ct_lt_mask(padding, 1);
+    bad |= ct_lt_mask(16, padding);
+    bad |= ct_lt_mask(length, padding);
+
     //
-    // get the padding length by reading the last byte
+    // every byte inside the padding must hold the padding length
     //
-    let padding = plain[CIPHER_LENGTH - 1] as usize;
+    let checked = if length < 16 { length } else { 16 };
+
+    let mut i = 0;
+    while i < checked {
+        let in_padding = ct_lt_mask(i, padding);
+        let diff = plain[length - 1 - i] ^ padding as u8;
 
-    plain[..CIPHER_LENGTH - padding].to_vec()
+        //
+        // fold any non zero diff into 0xFF
+        //
+        bad |= in_padding & (((diff as u16).wrapping_neg() >> 8) as u8);
+
+        i += 1;
+    }
+
+    bad
 }
 
-pub const fn decrypt_block(block: &[u8; 16], keys: &[[u8; 16]; 11]) -> [u8; 16] {
-    let mut decrypted = *block;
+///
+/// get the length of the data once the pkcs7 padding is removed.
+///
+/// Any padding error is reported as `Error::InvalidPadding` so that callers cannot act
+/// as a padding oracle by telling the different failures apart.
+///
+pub const fn pkcs7_unpadded_length(plain: &[u8]) -> Result<usize, Error> {
+    if plain.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+
+    if pkcs7_padding_mask(plain) != 0 {
+        return Err(Error::InvalidPadding);
+    }
+
+    Ok(plain.len() - plain[plain.len() - 1] as usize)
+}
+
+#[cfg(feature = "alloc")]
+pub fn try_remove_pkcs7_padding<const CIPHER_LENGTH: usize>(
+    plain: &[u8; CIPHER_LENGTH]
+) -> Result<Vec<u8>, Error> {
+    match pkcs7_unpadded_length(plain) {
+        Ok(length) => Ok(plain[..length].to_vec()),
+        Err(err) => Err(err),
+    }
+}
 
-    let rsbox: [u8; 256] = calculate_rsbox();
+#[cfg(feature = "alloc")]
+pub fn remove_pkcs7_padding<const CIPHER_LENGTH: usize>(plain: &[u8; CIPHER_LENGTH]) -> Vec<u8> {
+    match try_remove_pkcs7_padding(plain) {
+        Ok(plain) => plain,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+/// decrypt a single block, the number of rounds is given by the length of the key schedule
+pub const fn decrypt_block<const ROUND_KEYS: usize>(
+    block: &[u8; 16],
+    keys: &[[u8; 16]; ROUND_KEYS]
+) -> [u8; 16] {
+    let mut decrypted = *block;
 
     //
     // initial round
     //
-    add_round_key(&mut decrypted, &keys[10]);
+    add_round_key(&mut decrypted, &keys[ROUND_KEYS - 1]);
     shift_rows_rev(&mut decrypted);
-    sub_bytes_rev(&mut decrypted, &rsbox);
+    sub_bytes_rev(&mut decrypted, &RSBOX);
 
     //
     // main rounds
     //
-    let mut i = 9;
+    let mut i = ROUND_KEYS - 2;
     while i > 0 {
         add_round_key(&mut decrypted, &keys[i]);
         mix_columns_rev(&mut decrypted);
         shift_rows_rev(&mut decrypted);
-        sub_bytes_rev(&mut decrypted, &rsbox);
+        sub_bytes_rev(&mut decrypted, &RSBOX);
 
         i -= 1;
     }
@@ -137,9 +212,37 @@ pub const fn decrypt_block(block: &[u8; 16], keys: &[[u8; 16]; 11]) -> [u8; 16]
     decrypted
 }
 
+/// decrypt a single block using the equivalent inverse cipher (FIPS-197 section 5.3.5)
+///
+/// The keys must be a decryption schedule as built by `RoundKeys`.
+pub const fn equivalent_decrypt_block<const ROUND_KEYS: usize>(
+    block: &[u8; 16],
+    dec_keys: &[[u8; 16]; ROUND_KEYS]
+) -> [u8; 16] {
+    let mut decrypted = *block;
+
+    add_round_key(&mut decrypted, &dec_keys[0]);
+
+    let mut i = 1;
+    while i < ROUND_KEYS - 1 {
+        sub_bytes_rev(&mut decrypted, &RSBOX);
+        shift_rows_rev(&mut decrypted);
+        mix_columns_rev(&mut decrypted);
+        add_round_key(&mut decrypted, &dec_keys[i]);
+
+        i += 1;
+    }
+
+    sub_bytes_rev(&mut decrypted, &RSBOX);
+    shift_rows_rev(&mut decrypted);
+    add_round_key(&mut decrypted, &dec_keys[ROUND_KEYS - 1]);
+
+    decrypted
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::utils::{key_schedule, sub_bytes};
+    use crate::utils::{key_schedule, key_schedule_192, key_schedule_256, sub_bytes};
     use super::*;
 
     #[test]
@@ -259,5 +362,123 @@ mod tests {
 
         assert_eq!(DECRYPTED_BLOCK, EXPECTED);
     }
+
+    #[test]
+    fn test_pkcs7_unpadded_length(){
+        assert_eq!(pkcs7_unpadded_length(&[0x41, 0x42, 0x02, 0x02]), Ok(2));
+        assert_eq!(pkcs7_unpadded_length(&[0x10; 16]), Ok(0));
+        assert_eq!(pkcs7_unpadded_length(&[]), Err(Error::InvalidLength));
+        assert_eq!(pkcs7_unpadded_length(&[0x41, 0x00]), Err(Error::InvalidPadding));
+        assert_eq!(pkcs7_unpadded_length(&[0x11; 32]), Err(Error::InvalidPadding));
+        assert_eq!(pkcs7_unpadded_length(&[0x03, 0x03]), Err(Error::InvalidPadding));
+        assert_eq!(pkcs7_unpadded_length(&[0x41, 0x01, 0x03, 0x03]), Err(Error::InvalidPadding));
+    }
+
+    #[test]
+    fn test_pkcs7_padding_mask_exhaustive(){
+        //
+        // reference implementation with early returns
+        //
+        fn valid(plain: &[u8]) -> bool {
+            let padding = plain[plain.len() - 1] as usize;
+
+            padding != 0 && padding <= 16 && padding <= plain.len()
+                && plain[plain.len() - padding..].iter().all(|&byte| byte as usize == padding)
+        }
+
+        //
+        // every padding value with every single corrupted position
+        //
+        for length in [1, 5, 16, 32] {
+            for padding in 0..=255u8 {
+                for corrupt in 0..=length {
+                    let mut plain = [padding; 32];
+
+                    if corrupt < length - 1 {
+                        plain[corrupt] ^= 0x55;
+                    }
+
+                    let plain = &plain[..length];
+                    let mask = pkcs7_padding_mask(plain);
+
+                    assert_eq!(mask == 0, valid(plain), "length {length} padding {padding} corrupt {corrupt}");
+                    assert!(mask == 0 || mask == 0xFF);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pkcs7_unpadded_length_opaque_error(){
+        //
+        // an out of range padding byte and inconsistent padding bytes are indistinguishable
+        //
+        let out_of_range = pkcs7_unpadded_length(&[0x41; 16]);
+        let inconsistent = pkcs7_unpadded_length(&[0x41, 0x41, 0x01, 0x03, 0x03]);
+        let zero = pkcs7_unpadded_length(&[0x41, 0x00]);
+
+        assert_eq!(out_of_range, Err(Error::InvalidPadding));
+        assert_eq!(inconsistent, out_of_range);
+        assert_eq!(zero, out_of_range);
+    }
+
+    #[test]
+    fn test_decrypt_block_aes192(){
+        const KEY: [u8; 24] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17
+        ];
+
+        const BLOCK: [u8; 16] = [
+            0xdd, 0xa9, 0x7c, 0xa4,
+            0x86, 0x4c, 0xdf, 0xe0,
+            0x6e, 0xaf, 0x70, 0xa0,
+            0xec, 0x0d, 0x71, 0x91
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0x00, 0x11, 0x22, 0x33,
+            0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb,
+            0xcc, 0xdd, 0xee, 0xff
+        ];
+
+        const ROUND_KEYS: [[u8; 16]; 13] = key_schedule_192(&KEY);
+
+        const DECRYPTED_BLOCK: [u8; 16] = decrypt_block(&BLOCK, &ROUND_KEYS);
+
+        assert_eq!(DECRYPTED_BLOCK, EXPECTED);
+    }
+
+    #[test]
+    fn test_decrypt_block_aes256(){
+        const KEY: [u8; 32] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f
+        ];
+
+        const BLOCK: [u8; 16] = [
+            0x8e, 0xa2, 0xb7, 0xca,
+            0x51, 0x67, 0x45, 0xbf,
+            0xea, 0xfc, 0x49, 0x90,
+            0x4b, 0x49, 0x60, 0x89
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0x00, 0x11, 0x22, 0x33,
+            0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb,
+            0xcc, 0xdd, 0xee, 0xff
+        ];
+
+        const ROUND_KEYS: [[u8; 16]; 15] = key_schedule_256(&KEY);
+
+        const DECRYPTED_BLOCK: [u8; 16] = decrypt_block(&BLOCK, &ROUND_KEYS);
+
+        assert_eq!(DECRYPTED_BLOCK, EXPECTED);
+    }
 }
 
@@ -1,4 +1,4 @@
-use crate::utils::{add_round_key, gf_multiply, sub_bytes, SBOX};
+use crate::utils::{add_round_key, gf_multiply_table, sub_bytes, SBOX};
 
 pub const fn shift_rows(block: &mut [u8; 16]) {
     let temp = *block;
@@ -18,6 +18,10 @@ pub const fn shift_rows(block: &mut [u8; 16]) {
     }
 }
 
+/// multiplication tables used by MixColumns so that it only does lookups
+const MUL_2: [u8; 256] = gf_multiply_table(0x02);
+const MUL_3: [u8; 256] = gf_multiply_table(0x03);
+
 pub const fn mix_columns(block: &mut [u8; 16]) {
     let temp = *block;
 
@@ -27,32 +31,22 @@ pub const fn mix_columns(block: &mut [u8; 16]) {
         let i = col * 4;
 
         // matrix multiplication for each column
-        block[i] = gf_multiply(0x02, temp[i]) ^
-            gf_multiply(0x03, temp[i + 1]) ^
-            temp[i + 2] ^
-            temp[i + 3];
-
-        block[i + 1] = temp[i] ^
-            gf_multiply(0x02, temp[i + 1]) ^
-            gf_multiply(0x03, temp[i + 2]) ^
-            temp[i + 3];
-
-        block[i + 2] = temp[i] ^
-            temp[i + 1] ^
-            gf_multiply(0x02, temp[i + 2]) ^
-            gf_multiply(0x03, temp[i + 3]);
-
-        block[i + 3] = gf_multiply(0x03, temp[i]) ^
-            temp[i + 1] ^
-            temp[i + 2] ^
-            gf_multiply(0x02, temp[i + 3]);
+        let a = temp[i] as usize;
+        let b = temp[i + 1] as usize;
+        let c = temp[i + 2] as usize;
+        let d = temp[i + 3] as usize;
+
+        block[i] = MUL_2[a] ^ MUL_3[b] ^ temp[i + 2] ^ temp[i + 3];
+        block[i + 1] = temp[i] ^ MUL_2[b] ^ MUL_3[c] ^ temp[i + 3];
+        block[i + 2] = temp[i] ^ temp[i + 1] ^ MUL_2[c] ^ MUL_3[d];
+        block[i + 3] = MUL_3[a] ^ temp[i + 1] ^ temp[i + 2] ^ MUL_2[d];
 
         col += 1;
     }
 }
 
 pub const fn pkcs7_byte(block_length: usize) -> u8 {
-    if block_length % 16 != 0 {
+    if !block_length.is_multiple_of(16) {
         (16 - block_length % 16) as u8
     } else {
         16
@@ -60,20 +54,24 @@ pub const fn pkcs7_byte(block_length: usize) -> u8 {
 }
 
 pub const fn block_length_with_pkcs7_padding(block_length: usize) -> usize {
-    if block_length % 16 != 0 {
+    if !block_length.is_multiple_of(16) {
         block_length + (16 - block_length % 16)
     } else {
         block_length + 16
     }
 }
 
-pub const fn encrypt_block(block: &[u8; 16], keys: &[[u8; 16]; 11]) -> [u8; 16] {
+/// encrypt a single block, the number of rounds is given by the length of the key schedule
+pub const fn encrypt_block<const ROUND_KEYS: usize>(
+    block: &[u8; 16],
+    keys: &[[u8; 16]; ROUND_KEYS]
+) -> [u8; 16] {
     let mut encrypted = *block;
     add_round_key(&mut encrypted, &keys[0]);
 
     let mut i = 1;
 
-    while i < 10 {
+    while i < ROUND_KEYS - 1 {
         sub_bytes(&mut encrypted, &SBOX);
         shift_rows(&mut encrypted);
         mix_columns(&mut encrypted);
@@ -84,14 +82,14 @@ pub const fn encrypt_block(block: &[u8; 16], keys: &[[u8; 16]; 11]) -> [u8; 16]
 
     sub_bytes(&mut encrypted, &SBOX);
     shift_rows(&mut encrypted);
-    add_round_key(&mut encrypted, &keys[10]);
+    add_round_key(&mut encrypted, &keys[ROUND_KEYS - 1]);
 
     encrypted
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::{key_schedule, sub_bytes, SBOX};
+    use crate::utils::{key_schedule, key_schedule_192, key_schedule_256, sub_bytes, SBOX};
     use super::*;
 
     #[test]
@@ -183,4 +181,63 @@ mod tests {
 
         assert_eq!(ENCRYPTED_BLOCK, EXPECTED);
     }
+
+    #[test]
+    fn test_encrypt_block_aes192(){
+        const KEY: [u8; 24] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17
+        ];
+
+        const BLOCK: [u8; 16] = [
+            0x00, 0x11, 0x22, 0x33,
+            0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb,
+            0xcc, 0xdd, 0xee, 0xff
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0xdd, 0xa9, 0x7c, 0xa4,
+            0x86, 0x4c, 0xdf, 0xe0,
+            0x6e, 0xaf, 0x70, 0xa0,
+            0xec, 0x0d, 0x71, 0x91
+        ];
+
+        const ROUND_KEYS: [[u8; 16]; 13] = key_schedule_192(&KEY);
+
+        const ENCRYPTED_BLOCK: [u8; 16] = encrypt_block(&BLOCK, &ROUND_KEYS);
+
+        assert_eq!(ENCRYPTED_BLOCK, EXPECTED);
+    }
+
+    #[test]
+    fn test_encrypt_block_aes256(){
+        const KEY: [u8; 32] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f
+        ];
+
+        const BLOCK: [u8; 16] = [
+            0x00, 0x11, 0x22, 0x33,
+            0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb,
+            0xcc, 0xdd, 0xee, 0xff
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0x8e, 0xa2, 0xb7, 0xca,
+            0x51, 0x67, 0x45, 0xbf,
+            0xea, 0xfc, 0x49, 0x90,
+            0x4b, 0x49, 0x60, 0x89
+        ];
+
+        const ROUND_KEYS: [[u8; 16]; 15] = key_schedule_256(&KEY);
+
+        const ENCRYPTED_BLOCK: [u8; 16] = encrypt_block(&BLOCK, &ROUND_KEYS);
+
+        assert_eq!(ENCRYPTED_BLOCK, EXPECTED);
+    }
 }
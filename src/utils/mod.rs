@@ -1,5 +1,6 @@
 pub mod encrypt;
 pub mod decrypt;
+pub mod round_keys;
 
 pub const SBOX: [u8; 256] = [
     0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
@@ -66,73 +67,176 @@ pub const fn gf_multiply(a: u8, b: u8) -> u8 {
     result
 }
 
+/// table of the products of every byte with factor in the AES Galois Field
+pub const fn gf_multiply_table(factor: u8) -> [u8; 256] {
+    let mut table = [0u8; 256];
+
+    let mut i = 0;
+    while i < 256 {
+        table[i] = gf_multiply(factor, i as u8);
+        i += 1;
+    }
+
+    table
+}
+
 ///
-/// AES 128 bit key derivation algorithm.
+/// AES key derivation algorithm shared by every key size.
 ///
-/// It derives the encryption into 10 round keys to later encrypt blocks.
+/// The key is read as `KEY_LENGTH / 4` words and expanded into `ROUND_KEYS` round keys
+/// following FIPS-197 section 5.2.
 ///
-pub const fn key_schedule(key: &[u8; 16]) -> [[u8; 16]; 11] {
+pub const fn expand_key<const KEY_LENGTH: usize, const ROUND_KEYS: usize>(
+    key: &[u8; KEY_LENGTH]
+) -> [[u8; 16]; ROUND_KEYS] {
 
-    let mut keys = [[0u8; 16]; 11];
+    let mut keys = [[0u8; 16]; ROUND_KEYS];
+    let nk = KEY_LENGTH / 4;
 
     //
-    // first round key is the original key
+    // the first words of the schedule are the original key
     // so just copy it
     //
     let mut i = 0;
 
-    while i < 16 {
-        keys[0][i] = key[i];
+    while i < KEY_LENGTH {
+        keys[i / 16][i % 16] = key[i];
         i += 1;
     }
 
     //
-    // generate the remaining round keys
+    // generate the remaining words
     //
-    let mut round = 1;
+    let mut word = nk;
 
-    while round < 11 {
-
-        let mut temp = [0u8; 4];
-
-        //
-        // operations on the previous round key
-        //
-        let t = SBOX[keys[round - 1][12] as usize];
-        temp[0] = SBOX[keys[round - 1][13] as usize] ^ RCON[round - 1];
-        temp[1] = SBOX[keys[round - 1][14] as usize];
-        temp[2] = SBOX[keys[round - 1][15] as usize];
-        temp[3] = t;
+    while word < ROUND_KEYS * 4 {
 
         //
-        // generate new round key
+        // previous word
         //
-        i = 0;
-        while i < 4 {
+        let prev = (word - 1) * 4;
+        let mut temp = [
+            keys[prev / 16][prev % 16],
+            keys[prev / 16][prev % 16 + 1],
+            keys[prev / 16][prev % 16 + 2],
+            keys[prev / 16][prev % 16 + 3],
+        ];
 
+        if word.is_multiple_of(nk) {
+            //
+            // RotWord, SubWord and xor with the round constant
+            //
+            let t = temp[0];
+            temp[0] = SBOX[temp[1] as usize] ^ RCON[word / nk - 1];
+            temp[1] = SBOX[temp[2] as usize];
+            temp[2] = SBOX[temp[3] as usize];
+            temp[3] = SBOX[t as usize];
+        } else if nk > 6 && word % nk == 4 {
+            //
+            // AES 256 applies an extra SubWord in the middle of each key
+            //
             let mut j = 0;
-
             while j < 4 {
-                let idx = i * 4 + j;
-
-                if i == 0 {
-                    keys[round][idx] = keys[round - 1][idx] ^ temp[j];
-                } else {
-                    keys[round][idx] = keys[round - 1][idx] ^ keys[round][idx - 4];
-                }
-
+                temp[j] = SBOX[temp[j] as usize];
                 j += 1;
             }
+        }
 
-            i += 1;
+        //
+        // new word is the word nk positions earlier xored with temp
+        //
+        let back = (word - nk) * 4;
+        let idx = word * 4;
+
+        let mut j = 0;
+        while j < 4 {
+            keys[(idx + j) / 16][(idx + j) % 16] = keys[(back + j) / 16][(back + j) % 16] ^ temp[j];
+            j += 1;
         }
 
-        round += 1;
+        word += 1;
     }
 
     keys
 }
 
+///
+/// AES 128 bit key derivation algorithm.
+///
+/// It derives the encryption into 10 round keys to later encrypt blocks.
+///
+pub const fn key_schedule(key: &[u8; 16]) -> [[u8; 16]; 11] {
+    expand_key(key)
+}
+
+///
+/// AES 192 bit key derivation algorithm.
+///
+/// It derives the encryption into 12 round keys to later encrypt blocks.
+///
+pub const fn key_schedule_192(key: &[u8; 24]) -> [[u8; 16]; 13] {
+    expand_key(key)
+}
+
+///
+/// AES 256 bit key derivation algorithm.
+///
+/// It derives the encryption into 14 round keys to later encrypt blocks.
+///
+pub const fn key_schedule_256(key: &[u8; 32]) -> [[u8; 16]; 15] {
+    expand_key(key)
+}
+
+/// convert a string to bytes at compile time
+pub const fn str_to_bytes<const LENGTH: usize>(s: &str) -> [u8; LENGTH] {
+    //
+    // verify the string length at compile time
+    //
+    assert!(s.len() >= LENGTH, "String is shorter than LENGTH");
+
+    let mut arr = [0u8; LENGTH];
+    let bytes = s.as_bytes();
+    let mut i = 0;
+
+    //
+    // convert to bytes
+    //
+    while i < LENGTH {
+        arr[i] = bytes[i];
+        i += 1;
+    }
+
+    arr
+}
+
+/// convert a byte slice to an array at compile time
+pub const fn bytes_to_array<const LENGTH: usize>(bytes: &[u8]) -> [u8; LENGTH] {
+    assert!(bytes.len() >= LENGTH, "Slice is shorter than LENGTH");
+
+    let mut arr = [0u8; LENGTH];
+    let mut i = 0;
+
+    while i < LENGTH {
+        arr[i] = bytes[i];
+        i += 1;
+    }
+
+    arr
+}
+
+/// compare a tag with the first bytes of the expected one without an early exit
+pub const fn constant_time_eq(expected: &[u8], tag: &[u8]) -> bool {
+    let mut diff = 0;
+    let mut i = 0;
+
+    while i < tag.len() {
+        diff |= expected[i] ^ tag[i];
+        i += 1;
+    }
+
+    diff == 0
+}
+
 #[cfg(test)]
 mod tests {
     use crate::utils::decrypt::{calculate_rsbox, mix_columns_rev, shift_rows_rev};
@@ -240,4 +344,45 @@ mod tests {
 
         assert_eq!(ROUND_KEYS[10], EXPECTED);
     }
+
+    #[test]
+    fn test_key_schedule_aes192(){
+        const KEY: [u8; 24] = [
+            0x8e, 0x73, 0xb0, 0xf7, 0xda, 0x0e, 0x64, 0x52,
+            0xc8, 0x10, 0xf3, 0x2b, 0x80, 0x90, 0x79, 0xe5,
+            0x62, 0xf8, 0xea, 0xd2, 0x52, 0x2c, 0x6b, 0x7b
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0xe9, 0x8b, 0xa0, 0x6f,
+            0x44, 0x8c, 0x77, 0x3c,
+            0x8e, 0xcc, 0x72, 0x04,
+            0x01, 0x00, 0x22, 0x02
+        ];
+
+        const ROUND_KEYS: [[u8; 16]; 13] = key_schedule_192(&KEY);
+
+        assert_eq!(ROUND_KEYS[12], EXPECTED);
+    }
+
+    #[test]
+    fn test_key_schedule_aes256(){
+        const KEY: [u8; 32] = [
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe,
+            0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81,
+            0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7,
+            0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4
+        ];
+
+        const EXPECTED: [u8; 16] = [
+            0xfe, 0x48, 0x90, 0xd1,
+            0xe6, 0x18, 0x8d, 0x0b,
+            0x04, 0x6d, 0xf3, 0x44,
+            0x70, 0x6c, 0x63, 0x1e
+        ];
+
+        const ROUND_KEYS: [[u8; 16]; 15] = key_schedule_256(&KEY);
+
+        assert_eq!(ROUND_KEYS[14], EXPECTED);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,89 @@
+use crate::RoundKeys;
+use crate::utils::decrypt::mix_columns_rev;
+use crate::utils::{key_schedule, key_schedule_192, key_schedule_256};
+
+impl RoundKeys<11> {
+    pub const fn new(key: [u8; 16]) -> Self {
+        Self::from_schedule(key_schedule(&key))
+    }
+}
+
+impl RoundKeys<13> {
+    pub const fn new(key: [u8; 24]) -> Self {
+        Self::from_schedule(key_schedule_192(&key))
+    }
+}
+
+impl RoundKeys<15> {
+    pub const fn new(key: [u8; 32]) -> Self {
+        Self::from_schedule(key_schedule_256(&key))
+    }
+}
+
+impl<const ROUND_KEYS: usize> RoundKeys<ROUND_KEYS> {
+
+    ///
+    /// build the decryption schedule of the equivalent inverse cipher from the encryption one.
+    ///
+    /// The round keys are reversed and the inner ones go through InvMixColumns so that
+    /// decryption can apply the rounds in the same order as encryption.
+    ///
+    pub const fn from_schedule(enc: [[u8; 16]; ROUND_KEYS]) -> Self {
+        let mut dec = [[0u8; 16]; ROUND_KEYS];
+
+        let mut i = 0;
+
+        while i < ROUND_KEYS {
+            dec[i] = enc[ROUND_KEYS - 1 - i];
+
+            if i != 0 && i != ROUND_KEYS - 1 {
+                mix_columns_rev(&mut dec[i]);
+            }
+
+            i += 1;
+        }
+
+        Self {
+            enc,
+            dec,
+        }
+    }
+
+    pub const fn encryption(&self) -> &[[u8; 16]; ROUND_KEYS] {
+        &self.enc
+    }
+
+    pub const fn decryption(&self) -> &[[u8; 16]; ROUND_KEYS] {
+        &self.dec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RoundKeys;
+    use crate::utils::decrypt::{decrypt_block, equivalent_decrypt_block};
+    use crate::utils::encrypt::encrypt_block;
+
+    #[test]
+    fn test_equivalent_decrypt_block(){
+        const KEY: [u8; 32] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f
+        ];
+
+        const BLOCK: [u8; 16] = [
+            0x00, 0x11, 0x22, 0x33,
+            0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb,
+            0xcc, 0xdd, 0xee, 0xff
+        ];
+
+        const KEYS: RoundKeys<15> = RoundKeys::<15>::new(KEY);
+        const CIPHER: [u8; 16] = encrypt_block(&BLOCK, KEYS.encryption());
+
+        assert_eq!(equivalent_decrypt_block(&CIPHER, KEYS.decryption()), BLOCK);
+        assert_eq!(decrypt_block(&CIPHER, KEYS.encryption()), BLOCK);
+    }
+}